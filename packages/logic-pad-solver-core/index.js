@@ -1 +1 @@
-export { solveLogicPad } from "./src/solverBridge.js";
+export * from "./src/solverBridge.js";
@@ -0,0 +1,5 @@
+// Prints the JSON Schema of the puzzle format. Build with `--features schema`.
+
+fn main() {
+    println!("{}", solver::puzzle::json_schema());
+}
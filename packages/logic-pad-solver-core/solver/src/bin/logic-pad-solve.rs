@@ -0,0 +1,109 @@
+use std::io::Read;
+use std::process::ExitCode;
+
+use solver::{text, url, Puzzle};
+
+const USAGE: &str = "usage: logic-pad-solve [--underclued] [--json] [--print-puzzle] [FILE...]
+
+Solves Logic Pad puzzles given as JSON, in the text notation of the `text` module or as
+share links (read from stdin if no FILE or `-` is given; a FILE may also be a link itself).
+
+  --underclued    only show the cells which are common to all solutions
+  --json          print the result as JSON instead of a text grid
+  --print-puzzle  print the puzzle as JSON instead of solving it";
+
+fn is_url(s: &str) -> bool {
+    s.starts_with("https://") || s.starts_with("http://")
+}
+
+fn read_input(path: &str) -> Result<String, String> {
+    if path == "-" {
+        let mut buf = String::new();
+        std::io::stdin().read_to_string(&mut buf).map_err(|e| format!("stdin: {}", e))?;
+        Ok(buf)
+    } else {
+        std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))
+    }
+}
+
+// Returns whether the puzzle has a solution.
+fn run(path: &str, underclued: bool, json: bool, print_puzzle: bool) -> Result<bool, String> {
+    let input = if is_url(path) { path.to_string() } else { read_input(path)? };
+    // JSON always starts with `{`, which is not a valid tile in the text notation.
+    let puzzle: Puzzle = if input.trim_start().starts_with('{') {
+        Puzzle::from_json(&input).map_err(|e| format!("{}: {}", path, e))?
+    } else if is_url(input.trim()) {
+        url::parse_url(&input).map_err(|e| format!("{}: {}", path, e))?
+    } else {
+        text::parse_puzzle(&input).map_err(|e| format!("{}: {}", path, e))?
+    };
+
+    let problems = solver::validate(&puzzle);
+    if !problems.is_empty() {
+        let messages = problems.iter().map(|p| format!("{}: {}", path, p)).collect::<Vec<_>>();
+        return Err(messages.join("\n"));
+    }
+
+    if print_puzzle {
+        println!("{}", puzzle.to_json());
+        return Ok(true);
+    }
+
+    let board = solver::solve(&puzzle, underclued).map_err(|e| format!("{}: {}", path, e))?;
+
+    if json {
+        println!("{}", serde_json::to_string(&board).unwrap());
+    } else {
+        match &board {
+            Some(board) => print!("{}", text::format_board(&puzzle, board).map_err(|e| format!("{}: {}", path, e))?),
+            None => println!("no solution"),
+        }
+    }
+
+    Ok(board.is_some())
+}
+
+fn main() -> ExitCode {
+    let mut underclued = false;
+    let mut json = false;
+    let mut print_puzzle = false;
+    let mut paths = vec![];
+
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--underclued" => underclued = true,
+            "--json" => json = true,
+            "--print-puzzle" => print_puzzle = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            _ if arg.starts_with("--") => {
+                eprintln!("unknown option: {}\n{}", arg, USAGE);
+                return ExitCode::from(2);
+            }
+            _ => paths.push(arg),
+        }
+    }
+
+    if paths.is_empty() {
+        paths.push(String::from("-"));
+    }
+
+    let mut status = ExitCode::SUCCESS;
+    for path in &paths {
+        if paths.len() > 1 && !json && !print_puzzle {
+            println!("{}:", path);
+        }
+        match run(path, underclued, json, print_puzzle) {
+            Ok(true) => (),
+            Ok(false) => status = ExitCode::FAILURE,
+            Err(e) => {
+                eprintln!("{}", e);
+                status = ExitCode::from(2);
+            }
+        }
+    }
+
+    status
+}
@@ -0,0 +1,172 @@
+//! Rating puzzles by how strong the deductions needed to solve them are.
+//!
+//! The puzzle is solved in rounds. In each round, every cell which can be deduced with the weakest sufficient
+//! [`Strength`] from the cells found so far is filled. The solver interface cannot solve under assumptions, so the
+//! deductions are carried out on the clues instead: propagation deduces cells from each symbol or global rule on its
+//! own, as unit propagation deduces literals from each clause on its own, and probing assumes a color for a cell and
+//! propagates one step from it.
+//!
+//! A propagation round takes one restricted solve per clue, and a probing round at most one per clue and undecided
+//! cell, so the cost of a round grows with the size of the board times the number of clues.
+
+use serde::Serialize;
+
+use crate::error::SolverError;
+use crate::explain::{enumerate_clues, restrict, Clue};
+use crate::puzzle::{Color, Puzzle, Rule};
+use crate::solver::{self, Board, Uniqueness};
+use crate::verify::opposite;
+
+/// The kind of deduction which finds a cell. Tiles found so far, connections and offByX always take part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum Strength {
+    /// One step of propagation: a single symbol or global rule forces the cell on its own.
+    #[serde(rename = "propagation")]
+    Propagation,
+    /// Single-cell probing: one step of propagation from the other color of the cell runs into a contradiction.
+    #[serde(rename = "probing")]
+    Probing,
+    /// Deeper probing: the other color of the cell contradicts the whole puzzle.
+    #[serde(rename = "deepProbing")]
+    DeepProbing,
+}
+
+impl Strength {
+    fn weight(self) -> u32 {
+        match self {
+            Strength::Propagation => 1,
+            Strength::Probing => 4,
+            Strength::DeepProbing => 16,
+        }
+    }
+}
+
+/// A round of deductions, in which `cells` cells were found.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Round {
+    pub strength: Strength,
+    pub cells: usize,
+}
+
+/// The result of [`rate_difficulty`]. `score` is the sum of the weights of the rounds (1 for
+/// [`Strength::Propagation`], 4 for [`Strength::Probing`] and 16 for [`Strength::DeepProbing`]).
+#[derive(Debug, Clone, Serialize)]
+pub struct Difficulty {
+    pub rounds: Vec<Round>,
+    #[serde(rename = "maxStrength")]
+    pub max_strength: Option<Strength>,
+    pub score: u32,
+}
+
+// Cells found by a deduction, with their colors.
+type Deductions = Vec<(usize, usize, Color)>;
+
+// Cells which are undecided in `known` but forced in the puzzle restricted to any of `clue_sets`, or `None` if
+// one of the restricted puzzles has no solution or two of them force different colors on a cell.
+fn deduce(known: &Puzzle, clue_sets: &[Vec<Clue>]) -> Result<Option<Deductions>, SolverError> {
+    let mut found = vec![vec![None; known.width]; known.height];
+
+    for clues in clue_sets {
+        let facts = match solver::solve(&restrict(known, clues), true)? {
+            Some(facts) => facts,
+            None => return Ok(None),
+        };
+        for ((found_row, fact_row), tiles) in found.iter_mut().zip(&facts).zip(&known.tiles) {
+            for ((found, &fact), tile) in found_row.iter_mut().zip(fact_row).zip(tiles) {
+                if !tile.exists || tile.fixed || fact.is_none() {
+                    continue;
+                }
+                if found.is_some() && *found != fact {
+                    return Ok(None);
+                }
+                *found = fact;
+            }
+        }
+    }
+
+    let mut ret = vec![];
+    for (y, row) in found.iter().enumerate() {
+        for (x, &color) in row.iter().enumerate() {
+            if let Some(color) = color {
+                ret.push((y, x, color));
+            }
+        }
+    }
+    Ok(Some(ret))
+}
+
+fn fix_cells(known: &mut Puzzle, cells: &[(usize, usize, Color)]) {
+    for &(y, x, color) in cells {
+        let tile = &mut known.tiles[y][x];
+        tile.fixed = true;
+        tile.color = color;
+    }
+}
+
+// Cells which are undecided in `known` and whose color other than in `solution` runs into a contradiction after one
+// step of propagation. Only that color is probed, since the solution satisfies every clue.
+fn probe(known: &Puzzle, solution: &Board, singles: &[Vec<Clue>]) -> Result<Deductions, SolverError> {
+    let mut found = vec![];
+    for (y, (tiles, colors)) in known.tiles.iter().zip(solution).enumerate() {
+        for (x, (tile, &color)) in tiles.iter().zip(colors).enumerate() {
+            let color = match color {
+                Some(color) if tile.exists && !tile.fixed => color,
+                _ => continue,
+            };
+            let mut assumed = known.clone();
+            fix_cells(&mut assumed, &[(y, x, opposite(color))]);
+            if deduce(&assumed, singles)?.is_none() {
+                found.push((y, x, color));
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// Solves `puzzle` in rounds of deductions of increasing [`Strength`] and reports the rounds.
+/// Returns `None` if `puzzle` does not have a unique solution.
+///
+/// symbolCount rules only take part in [`Strength::DeepProbing`] deductions, since dropping the symbols they count
+/// would strengthen them rather than relax them.
+pub fn rate_difficulty(puzzle: &Puzzle) -> Result<Option<Difficulty>, SolverError> {
+    let solution = match solver::check_uniqueness(puzzle)? {
+        Uniqueness::Unique { solution } => solution,
+        _ => return Ok(None),
+    };
+
+    let clues = enumerate_clues(puzzle)
+        .into_iter()
+        .filter(|clue| !matches!(clue, &Clue::Rule { rule } if matches!(puzzle.rules[rule], Rule::SymbolCount { .. })))
+        .collect::<Vec<_>>();
+    let singles = clues.iter().map(|&clue| vec![clue]).collect::<Vec<_>>();
+
+    // `known` only has colors of the unique solution, so deductions from it never fail.
+    let mut known = puzzle.clone();
+    let mut rounds = vec![];
+    loop {
+        let remaining = known.tiles.iter().flatten().filter(|tile| tile.exists && !tile.fixed).count();
+        if remaining == 0 {
+            break;
+        }
+
+        let mut strength = Strength::Propagation;
+        let mut found = deduce(&known, &singles)?.unwrap_or_default();
+        if found.is_empty() {
+            strength = Strength::Probing;
+            found = probe(&known, &solution, &singles)?;
+        }
+        if found.is_empty() {
+            strength = Strength::DeepProbing;
+            found = deduce(&known, &[enumerate_clues(puzzle)])?.unwrap_or_default();
+        }
+
+        fix_cells(&mut known, &found);
+        rounds.push(Round { strength, cells: found.len() });
+    }
+
+    Ok(Some(Difficulty {
+        max_strength: rounds.iter().map(|round| round.strength).max(),
+        score: rounds.iter().map(|round| round.strength.weight()).sum(),
+        rounds,
+    }))
+}
@@ -0,0 +1,99 @@
+use std::fmt;
+
+use serde::Serialize;
+
+/// Errors are serialized with a machine-readable `code` together with the index of the offending rule
+/// (an index of `Puzzle::rules`) or connection and the coordinates of the offending tile, where applicable.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "code")]
+pub enum SolverError {
+    #[serde(rename = "invalidJson")]
+    InvalidJson { message: String },
+    #[serde(rename = "unsupportedVersion")]
+    UnsupportedVersion { version: u64 },
+    #[serde(rename = "invalidSize")]
+    InvalidSize,
+    #[serde(rename = "tileRowCountMismatch")]
+    TileRowCountMismatch,
+    #[serde(rename = "tileColumnCountMismatch")]
+    TileColumnCountMismatch { y: usize },
+    #[serde(rename = "connectionOutOfBounds")]
+    ConnectionOutOfBounds { connection: usize },
+    #[serde(rename = "fixedGrayTile")]
+    FixedGrayTile { y: usize, x: usize },
+    #[serde(rename = "grayColor")]
+    GrayColor { rule: usize },
+    #[serde(rename = "invalidForbiddenPattern")]
+    InvalidForbiddenPattern { rule: usize },
+    #[serde(rename = "emptyForbiddenPattern")]
+    EmptyForbiddenPattern { rule: usize },
+    #[serde(rename = "invalidNumber")]
+    InvalidNumber { rule: usize },
+    #[serde(rename = "duplicateAreaNumber")]
+    DuplicateAreaNumber { rule: usize, y: usize, x: usize },
+    #[serde(rename = "symbolOnNonExistingTile")]
+    SymbolOnNonExistingTile { rule: usize, y: usize, x: usize },
+    #[serde(rename = "symbolOutOfBounds")]
+    SymbolOutOfBounds { rule: usize, y: usize, x: usize },
+    #[serde(rename = "invalidLotusPosition")]
+    InvalidLotusPosition { rule: usize, y: usize, x: usize },
+    #[serde(rename = "galaxyOnCorner")]
+    GalaxyOnCorner { rule: usize, y: usize, x: usize },
+    #[serde(rename = "symbolNotOnCellCenter")]
+    SymbolNotOnCellCenter { rule: usize, y: usize, x: usize },
+    #[serde(rename = "multipleOffByX")]
+    MultipleOffByX { rule: usize },
+    #[serde(rename = "negativeOffByX")]
+    NegativeOffByX { rule: usize },
+    #[serde(rename = "multipleAreaNumberRules")]
+    MultipleAreaNumberRules { rule: usize },
+    #[serde(rename = "multipleLyingSymbolsRules")]
+    MultipleLyingSymbolsRules { rule: usize },
+    #[serde(rename = "multipleRegionAreaRules")]
+    MultipleRegionAreaRules { rule: usize },
+    #[serde(rename = "gridSizeMismatch")]
+    GridSizeMismatch,
+    #[serde(rename = "inconsistentGrid")]
+    InconsistentGrid,
+    #[serde(rename = "unsupportedTimeLimit")]
+    UnsupportedTimeLimit,
+    #[serde(rename = "inconsistentSolver")]
+    InconsistentSolver,
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolverError::InvalidJson { message } => write!(f, "{}", message),
+            SolverError::UnsupportedVersion { version } => write!(f, "unsupported puzzle version {}", version),
+            SolverError::InvalidSize => write!(f, "width and height must be positive"),
+            SolverError::TileRowCountMismatch => write!(f, "number of tile rows does not match height"),
+            SolverError::TileColumnCountMismatch { .. } => write!(f, "number of tiles in a row does not match width"),
+            SolverError::ConnectionOutOfBounds { .. } => write!(f, "connection out of bounds"),
+            SolverError::FixedGrayTile { .. } => write!(f, "gray tile is fixed"),
+            SolverError::GrayColor { .. } => write!(f, "rule with gray color"),
+            SolverError::InvalidForbiddenPattern { .. } => write!(f, "forbidden pattern is empty or not rectangular"),
+            SolverError::EmptyForbiddenPattern { .. } => write!(f, "empty forbidden pattern"),
+            SolverError::InvalidNumber { .. } => write!(f, "invalid number in rule"),
+            SolverError::DuplicateAreaNumber { .. } => write!(f, "duplicate area number"),
+            SolverError::SymbolOnNonExistingTile { .. } => write!(f, "symbol on non-existing tile; don't do this"),
+            SolverError::SymbolOutOfBounds { .. } => write!(f, "symbol out of bounds"),
+            SolverError::InvalidLotusPosition { .. } => write!(f, "lotus on invalid position"),
+            SolverError::GalaxyOnCorner { .. } => write!(f, "galaxies on corners may cause unexpected behavior"),
+            SolverError::SymbolNotOnCellCenter { .. } => {
+                write!(f, "symbol not on cell center is not supported for symbol count")
+            }
+            SolverError::MultipleOffByX { .. } => write!(f, "multiple offByX rules"),
+            SolverError::NegativeOffByX { .. } => write!(f, "offByX with negative number"),
+            SolverError::MultipleAreaNumberRules { .. } => write!(f, "multiple area number rules"),
+            SolverError::MultipleLyingSymbolsRules { .. } => write!(f, "multiple lyingSymbols rules"),
+            SolverError::MultipleRegionAreaRules { .. } => write!(f, "multiple area size rules for the same color"),
+            SolverError::GridSizeMismatch => write!(f, "grid size mismatch"),
+            SolverError::InconsistentGrid => write!(f, "the current grid has no solution"),
+            SolverError::UnsupportedTimeLimit => write!(f, "time limit is not supported on this target"),
+            SolverError::InconsistentSolver => write!(f, "the solver disagrees with the verifier"),
+        }
+    }
+}
+
+impl std::error::Error for SolverError {}
@@ -0,0 +1,438 @@
+use serde::Serialize;
+
+use crate::error::SolverError;
+use crate::puzzle::{Color, Puzzle, Rule};
+use crate::solver::{self, Uniqueness};
+use crate::validate;
+
+/// A clue which can be dropped from a puzzle independently of the others.
+/// Coordinates of symbols are the same as in the input (i.e. doubled for lotuses and galaxies).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "type")]
+pub enum Clue {
+    #[serde(rename = "rule")]
+    Rule { rule: usize },
+    #[serde(rename = "symbol")]
+    Symbol { rule: usize, index: usize, y: usize, x: usize },
+    #[serde(rename = "fixedTile")]
+    FixedTile { y: usize, x: usize },
+    /// `connection` is an index of `Puzzle::connections`.
+    #[serde(rename = "connection")]
+    Connection { connection: usize },
+}
+
+/// A cell whose color is forced, together with clues which suffice to force it.
+#[derive(Debug, Clone, Serialize)]
+pub struct Hint {
+    pub y: usize,
+    pub x: usize,
+    pub color: Color,
+    pub clues: Vec<Clue>,
+}
+
+fn symbol_positions(rule: &Rule) -> Option<Vec<(usize, usize)>> {
+    match rule {
+        Rule::Minesweeper { tiles } => Some(tiles.iter().map(|t| (t.y, t.x)).collect()),
+        Rule::AreaNumber { tiles } => Some(tiles.iter().map(|t| (t.y, t.x)).collect()),
+        Rule::Letter { tiles } => Some(tiles.iter().map(|t| (t.y, t.x)).collect()),
+        Rule::Dart { tiles } => Some(tiles.iter().map(|t| (t.y, t.x)).collect()),
+        Rule::Viewpoint { tiles } => Some(tiles.iter().map(|t| (t.y, t.x)).collect()),
+        Rule::Lotus { tiles } => Some(tiles.iter().map(|t| (t.y, t.x)).collect()),
+        Rule::Galaxy { tiles } => Some(tiles.iter().map(|t| (t.y, t.x)).collect()),
+        _ => None,
+    }
+}
+
+fn retain_symbols<T: Clone>(tiles: &[T], keep: impl Fn(usize) -> bool) -> Vec<T> {
+    tiles
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| keep(i))
+        .map(|(_, t)| t.clone())
+        .collect()
+}
+
+fn lying_symbols_rule(puzzle: &Puzzle) -> Option<usize> {
+    puzzle.rules.iter().position(|rule| matches!(rule, Rule::LyingSymbols { .. }))
+}
+
+/// Lists all clues of `puzzle` which can be dropped by [`restrict`], i.e. except fixed tiles and connections.
+///
+/// If `puzzle` has a lyingSymbols rule, symbols are not clues of their own, since dropping one of them would
+/// change how many of the others are false. The lyingSymbols rule stands for all of them instead.
+pub fn enumerate_clues(puzzle: &Puzzle) -> Vec<Clue> {
+    let lying_symbols = lying_symbols_rule(puzzle).is_some();
+    let mut clues = vec![];
+
+    for (i, rule) in puzzle.rules.iter().enumerate() {
+        if lying_symbols && symbol_positions(rule).is_some() {
+            continue;
+        }
+        if let Some(positions) = symbol_positions(rule) {
+            for (index, (y, x)) in positions.into_iter().enumerate() {
+                clues.push(Clue::Symbol { rule: i, index, y, x });
+            }
+        } else if let Rule::OffByX { number: _ } = rule {
+            // offByX changes the meaning of other clues rather than adding a constraint,
+            // so it is never dropped.
+        } else {
+            clues.push(Clue::Rule { rule: i });
+        }
+    }
+
+    clues
+}
+
+// Whether `restrict` keeps all symbols of `puzzle` because of a symbolCount or lyingSymbols rule in `clues`.
+fn keeps_all_symbols(puzzle: &Puzzle, clues: &[Clue]) -> bool {
+    puzzle.rules.iter().enumerate().any(|(i, rule)| {
+        matches!(rule, Rule::SymbolCount { .. } | Rule::LyingSymbols { .. }) && clues.contains(&Clue::Rule { rule: i })
+    })
+}
+
+/// Adds to `clues` the symbols which [`restrict`] keeps along with a symbolCount or lyingSymbols rule in `clues`,
+/// so that the result lists every clue of the restricted puzzle. Clues are ordered as in [`enumerate_all_clues`].
+pub fn with_kept_symbols(puzzle: &Puzzle, clues: &[Clue]) -> Vec<Clue> {
+    let all_symbols = keeps_all_symbols(puzzle, clues);
+    let mut ret = vec![];
+
+    for (i, rule) in puzzle.rules.iter().enumerate() {
+        if let Some(positions) = symbol_positions(rule) {
+            for (index, (y, x)) in positions.into_iter().enumerate() {
+                let symbol = Clue::Symbol { rule: i, index, y, x };
+                if all_symbols || clues.contains(&symbol) {
+                    ret.push(symbol);
+                }
+            }
+        } else if clues.contains(&Clue::Rule { rule: i }) {
+            ret.push(Clue::Rule { rule: i });
+        }
+    }
+    ret.extend(clues.iter().filter(|c| matches!(c, Clue::FixedTile { .. } | Clue::Connection { .. })));
+
+    ret
+}
+
+/// Returns a copy of `puzzle` which keeps only the clues in `clues`.
+/// Tiles, connections and the offByX rule are always kept, and [`Clue::FixedTile`] and [`Clue::Connection`] in
+/// `clues` are ignored.
+///
+/// If a symbolCount or lyingSymbols rule is kept, all symbols are kept (and so is the lyingSymbols rule), since
+/// dropping symbols would strengthen these rules rather than relax the puzzle.
+pub fn restrict(puzzle: &Puzzle, clues: &[Clue]) -> Puzzle {
+    let lying_symbols = lying_symbols_rule(puzzle);
+    let all_symbols = keeps_all_symbols(puzzle, clues);
+    let mut rules = vec![];
+
+    for (i, rule) in puzzle.rules.iter().enumerate() {
+        let keep = |index: usize| {
+            all_symbols
+                || (lying_symbols.is_none()
+                    && clues.iter().any(|c| matches!(c, &Clue::Symbol { rule, index: j, .. } if rule == i && j == index)))
+        };
+
+        let rule = match rule {
+            Rule::Minesweeper { tiles } => Rule::Minesweeper { tiles: retain_symbols(tiles, keep) },
+            Rule::AreaNumber { tiles } => Rule::AreaNumber { tiles: retain_symbols(tiles, keep) },
+            Rule::Letter { tiles } => Rule::Letter { tiles: retain_symbols(tiles, keep) },
+            Rule::Dart { tiles } => Rule::Dart { tiles: retain_symbols(tiles, keep) },
+            Rule::Viewpoint { tiles } => Rule::Viewpoint { tiles: retain_symbols(tiles, keep) },
+            Rule::Lotus { tiles } => Rule::Lotus { tiles: retain_symbols(tiles, keep) },
+            Rule::Galaxy { tiles } => Rule::Galaxy { tiles: retain_symbols(tiles, keep) },
+            Rule::OffByX { number: _ } => rule.clone(),
+            Rule::LyingSymbols { number: _ } => {
+                if !all_symbols {
+                    continue;
+                }
+                rule.clone()
+            }
+            _ => {
+                if !clues.contains(&Clue::Rule { rule: i }) {
+                    continue;
+                }
+                rule.clone()
+            }
+        };
+        rules.push(rule);
+    }
+
+    Puzzle {
+        version: puzzle.version,
+        width: puzzle.width,
+        height: puzzle.height,
+        tiles: puzzle.tiles.clone(),
+        rules,
+        connections: puzzle.connections.clone(),
+    }
+}
+
+/// Lists all clues of `puzzle` including fixed tiles and connections, which can be dropped by [`restrict_all`].
+pub fn enumerate_all_clues(puzzle: &Puzzle) -> Vec<Clue> {
+    let mut clues = enumerate_clues(puzzle);
+
+    for y in 0..puzzle.height {
+        for x in 0..puzzle.width {
+            let tile = &puzzle.tiles[y][x];
+            if tile.exists && tile.fixed {
+                clues.push(Clue::FixedTile { y, x });
+            }
+        }
+    }
+    for i in 0..puzzle.connections.len() {
+        clues.push(Clue::Connection { connection: i });
+    }
+
+    clues
+}
+
+/// Same as [`restrict`], but also unfixes the tiles and drops the connections which are not in `clues`.
+pub fn restrict_all(puzzle: &Puzzle, clues: &[Clue]) -> Puzzle {
+    let mut ret = restrict(puzzle, clues);
+
+    for y in 0..puzzle.height {
+        for x in 0..puzzle.width {
+            let tile = &mut ret.tiles[y][x];
+            if tile.fixed && !clues.contains(&Clue::FixedTile { y, x }) {
+                tile.fixed = false;
+                tile.color = Color::Undecided;
+            }
+        }
+    }
+    ret.connections = puzzle
+        .connections
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| clues.contains(&Clue::Connection { connection: i }))
+        .map(|(_, c)| c.clone())
+        .collect();
+
+    ret
+}
+
+// Shrinks `clues` to an irreducible subset for which `holds` is still true.
+// `holds` must be true for `clues` itself.
+// Each check re-encodes the restricted puzzle instead of switching clues by assumption literals,
+// since the solver interface does not expose solving under assumptions.
+fn shrink_clues(
+    clues: &[Clue],
+    mut holds: impl FnMut(&[Clue]) -> Result<bool, SolverError>,
+) -> Result<Vec<Clue>, SolverError> {
+    let mut current = clues.to_vec();
+    let mut i = 0;
+
+    while i < current.len() {
+        let mut candidate = current.clone();
+        candidate.remove(i);
+
+        if holds(&candidate)? {
+            current = candidate;
+        } else {
+            i += 1;
+        }
+    }
+
+    Ok(current)
+}
+
+fn fix_cell(puzzle: &mut Puzzle, y: usize, x: usize, color: Color) {
+    let tile = &mut puzzle.tiles[y][x];
+    tile.fixed = true;
+    tile.color = color;
+}
+
+fn opposite(color: Color) -> Color {
+    match color {
+        Color::White => Color::Black,
+        Color::Black => Color::White,
+        Color::Undecided => Color::Undecided,
+    }
+}
+
+// Returns a copy of `puzzle` in which the cells colored in `grid` are fixed.
+// The cells are fixed in the puzzle rather than passed as assumption literals, for the same reason as in `shrink_clues`.
+fn apply_grid(puzzle: &Puzzle, grid: &[Vec<Color>]) -> Result<Puzzle, SolverError> {
+    validate::check(puzzle)?;
+    if grid.len() != puzzle.height || grid.iter().any(|row| row.len() != puzzle.width) {
+        return Err(SolverError::GridSizeMismatch);
+    }
+
+    let mut ret = puzzle.clone();
+    for (y, row) in grid.iter().enumerate() {
+        for (x, &color) in row.iter().enumerate() {
+            let tile = &puzzle.tiles[y][x];
+            if tile.exists && !tile.fixed && color != Color::Undecided {
+                fix_cell(&mut ret, y, x, color);
+            }
+        }
+    }
+
+    Ok(ret)
+}
+
+/// Finds a cell which is undecided in `grid` but whose color is forced by the puzzle
+/// together with the cells already colored in `grid`, along with an irreducible set of
+/// clues which suffices to force it. As in [`explain_contradiction`], symbols kept along with a symbolCount or
+/// lyingSymbols rule are listed too.
+/// Returns `None` if no cell can be deduced (e.g. the grid is complete or the puzzle is underclued).
+pub fn next_hint(puzzle: &Puzzle, grid: &[Vec<Color>]) -> Result<Option<Hint>, SolverError> {
+    let base = apply_grid(puzzle, grid)?;
+
+    let facts = match solver::solve(&base, true)? {
+        Some(facts) => facts,
+        None => return Err(SolverError::InconsistentGrid),
+    };
+
+    let mut target = None;
+    'outer: for (y, row) in facts.iter().enumerate() {
+        for (x, &fact) in row.iter().enumerate() {
+            if base.tiles[y][x].fixed {
+                continue;
+            }
+            if let Some(color) = fact {
+                target = Some((y, x, color));
+                break 'outer;
+            }
+        }
+    }
+
+    let (y, x, color) = match target {
+        Some(target) => target,
+        None => return Ok(None),
+    };
+
+    let mut refuted = base;
+    fix_cell(&mut refuted, y, x, opposite(color));
+
+    let clues = shrink_clues(&enumerate_clues(&refuted), |clues| {
+        Ok(solver::solve(&restrict(&refuted, clues), false)?.is_none())
+    })?;
+
+    Ok(Some(Hint { y, x, color, clues: with_kept_symbols(&refuted, &clues) }))
+}
+
+/// The result of [`check_grid`].
+#[derive(Debug, Clone, Serialize)]
+pub struct GridCheck {
+    /// Cells colored in the grid with a color which they have in no solution of the puzzle.
+    pub mistakes: Vec<Mistake>,
+    /// Whether some solution agrees with all colored cells of the grid.
+    pub completable: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Mistake {
+    pub y: usize,
+    pub x: usize,
+}
+
+/// Checks a partially colored `grid` (as in [`next_hint`]). A cell is a mistake only if its color is excluded by
+/// every solution, so a grid can be free of mistakes and still not be completable when its cells are wrong only
+/// in combination.
+pub fn check_grid(puzzle: &Puzzle, grid: &[Vec<Color>]) -> Result<GridCheck, SolverError> {
+    let base = apply_grid(puzzle, grid)?;
+
+    let facts = solver::solve(puzzle, true)?;
+    let mut mistakes = vec![];
+    for y in 0..puzzle.height {
+        for x in 0..puzzle.width {
+            if puzzle.tiles[y][x].fixed || !base.tiles[y][x].fixed {
+                continue;
+            }
+            // Without any solution, every colored cell is excluded.
+            let excluded = match &facts {
+                Some(facts) => facts[y][x] == Some(opposite(grid[y][x])),
+                None => true,
+            };
+            if excluded {
+                mistakes.push(Mistake { y, x });
+            }
+        }
+    }
+
+    let completable = facts.is_some() && mistakes.is_empty() && solver::solve(&base, false)?.is_some();
+
+    Ok(GridCheck { mistakes, completable })
+}
+
+/// Returns an irreducible set of clues which contradict each other, or `None` if the puzzle has a solution.
+/// The set is empty if the tiles and connections are contradictory by themselves.
+///
+/// Symbols kept along with a symbolCount or lyingSymbols rule in the set are listed too (see [`with_kept_symbols`]),
+/// so that the clues contradict each other on their own.
+pub fn explain_contradiction(puzzle: &Puzzle) -> Result<Option<Vec<Clue>>, SolverError> {
+    if solver::solve(puzzle, false)?.is_some() {
+        return Ok(None);
+    }
+
+    let clues = shrink_clues(&enumerate_clues(puzzle), |clues| {
+        Ok(solver::solve(&restrict(puzzle, clues), false)?.is_none())
+    })?;
+
+    Ok(Some(with_kept_symbols(puzzle, &clues)))
+}
+
+/// The result of [`minimize_clues`]. `removed` lists the clues of the original puzzle which were dropped.
+#[derive(Debug, Clone, Serialize)]
+pub struct Minimized {
+    pub puzzle: Puzzle,
+    pub removed: Vec<Clue>,
+}
+
+/// Drops symbols, fixed tiles and connections from `puzzle` one by one as long as the solution stays unique and
+/// the same, so that no remaining one can be dropped on its own. Global rules are always kept, and so are symbols
+/// if `puzzle` has a symbolCount or lyingSymbols rule (see [`restrict`]), so that only fixed tiles and connections
+/// can be dropped from such a puzzle.
+/// Returns `None` if `puzzle` does not have a unique solution.
+pub fn minimize_clues(puzzle: &Puzzle) -> Result<Option<Minimized>, SolverError> {
+    let solution = match solver::check_uniqueness(puzzle)? {
+        Uniqueness::Unique { solution } => solution,
+        _ => return Ok(None),
+    };
+
+    // Under lyingSymbols, symbols are not clues of their own (see `enumerate_clues`).
+    let symbol_count = puzzle.rules.iter().any(|rule| matches!(rule, Rule::SymbolCount { .. }));
+    let (always_kept, removable): (Vec<Clue>, Vec<Clue>) = enumerate_all_clues(puzzle)
+        .into_iter()
+        .partition(|c| matches!(c, Clue::Rule { .. }) || (symbol_count && matches!(c, Clue::Symbol { .. })));
+
+    let kept = shrink_clues(&removable, |clues| {
+        let clues = always_kept.iter().chain(clues).copied().collect::<Vec<_>>();
+        Ok(matches!(
+            solver::check_uniqueness(&restrict_all(puzzle, &clues))?,
+            Uniqueness::Unique { solution: reduced } if reduced == solution
+        ))
+    })?;
+
+    let removed = removable.into_iter().filter(|c| !kept.contains(c)).collect();
+    let clues = always_kept.into_iter().chain(kept).collect::<Vec<_>>();
+
+    Ok(Some(Minimized { puzzle: restrict_all(puzzle, &clues), removed }))
+}
+
+/// Returns the indices of the rules of `puzzle` (including the rules holding symbols) without which the solution
+/// would still be unique and the same, or `None` if `puzzle` does not have a unique solution.
+///
+/// Dropping a rule may change the unique solution (e.g. offByX, or symbols counted by symbolCount) or make the puzzle
+/// invalid, in which case the rule is not redundant. Each rule is checked on its own, so dropping two redundant rules at once may make the
+/// solution ambiguous.
+pub fn redundant_rules(puzzle: &Puzzle) -> Result<Option<Vec<usize>>, SolverError> {
+    let solution = match solver::check_uniqueness(puzzle)? {
+        Uniqueness::Unique { solution } => solution,
+        _ => return Ok(None),
+    };
+
+    let mut redundant = vec![];
+    for i in 0..puzzle.rules.len() {
+        let mut reduced = puzzle.clone();
+        reduced.rules.remove(i);
+        // A rule whose removal leaves an invalid puzzle is needed by the puzzle.
+        if !validate::validate(&reduced).is_empty() {
+            continue;
+        }
+        if matches!(solver::check_uniqueness(&reduced)?, Uniqueness::Unique { solution: s } if s == solution) {
+            redundant.push(i);
+        }
+    }
+
+    Ok(Some(redundant))
+}
@@ -0,0 +1,147 @@
+// C ABI exports used by the emscripten build (see `src/solverBridge.js`).
+// Every export takes a JSON-encoded `Puzzle` and returns a newly allocated `ResultBuffer` holding the JSON-encoded
+// result, which is read by `result_data` / `result_len` and must be released by `free_result`.
+// No state is shared between calls, so exports may be called concurrently from different threads.
+
+use std::time::Duration;
+
+use crate::{json, solver};
+
+// The result of an export, owned by the caller.
+struct ResultBuffer {
+    data: Vec<u8>,
+}
+
+fn into_result_buffer(result: String) -> *mut ResultBuffer {
+    Box::into_raw(Box::new(ResultBuffer { data: result.into_bytes() }))
+}
+
+#[no_mangle]
+extern "C" fn result_data(result: *const ResultBuffer) -> *const u8 {
+    let result = unsafe { &*result };
+    result.data.as_ptr()
+}
+
+#[no_mangle]
+extern "C" fn result_len(result: *const ResultBuffer) -> usize {
+    let result = unsafe { &*result };
+    result.data.len()
+}
+
+#[no_mangle]
+extern "C" fn free_result(result: *mut ResultBuffer) {
+    if !result.is_null() {
+        drop(unsafe { Box::from_raw(result) });
+    }
+}
+
+#[no_mangle]
+extern "C" fn solve_puzzle(data: *const u8, len: usize, underclued: i32) -> *mut ResultBuffer {
+    let data = unsafe { std::slice::from_raw_parts(data, len) };
+    into_result_buffer(json::solve(data, underclued != 0))
+}
+
+// A negative `time_limit_ms` or `max_solver_calls` means no limit.
+fn budget(time_limit_ms: i32, max_solver_calls: i32) -> solver::Budget {
+    solver::Budget {
+        time_limit: (time_limit_ms >= 0).then(|| Duration::from_millis(time_limit_ms as u64)),
+        max_solver_calls: (max_solver_calls >= 0).then_some(max_solver_calls as usize),
+    }
+}
+
+#[no_mangle]
+extern "C" fn solve_puzzle_with_budget(
+    data: *const u8,
+    len: usize,
+    time_limit_ms: i32,
+    max_solver_calls: i32,
+) -> *mut ResultBuffer {
+    let data = unsafe { std::slice::from_raw_parts(data, len) };
+    into_result_buffer(json::solve_with_budget(data, &budget(time_limit_ms, max_solver_calls)))
+}
+
+// `on_fact` is called with the JSON of each proven cell, which is valid only during the call.
+#[no_mangle]
+extern "C" fn solve_puzzle_with_progress(
+    data: *const u8,
+    len: usize,
+    time_limit_ms: i32,
+    max_solver_calls: i32,
+    on_fact: extern "C" fn(*const u8, usize),
+) -> *mut ResultBuffer {
+    let data = unsafe { std::slice::from_raw_parts(data, len) };
+    into_result_buffer(json::solve_with_progress(
+        data,
+        &budget(time_limit_ms, max_solver_calls),
+        |fact| on_fact(fact.as_ptr(), fact.len()),
+    ))
+}
+
+#[no_mangle]
+extern "C" fn enumerate_solutions(data: *const u8, len: usize, limit: i32) -> *mut ResultBuffer {
+    let data = unsafe { std::slice::from_raw_parts(data, len) };
+    into_result_buffer(json::enumerate_solutions(data, limit.max(0) as usize))
+}
+
+#[no_mangle]
+extern "C" fn check_uniqueness(data: *const u8, len: usize) -> *mut ResultBuffer {
+    let data = unsafe { std::slice::from_raw_parts(data, len) };
+    into_result_buffer(json::check_uniqueness(data))
+}
+
+#[no_mangle]
+extern "C" fn count_solutions(data: *const u8, len: usize, cap: i32) -> *mut ResultBuffer {
+    let data = unsafe { std::slice::from_raw_parts(data, len) };
+    into_result_buffer(json::count_solutions(data, cap.max(0) as usize))
+}
+
+#[no_mangle]
+extern "C" fn next_hint(data: *const u8, len: usize, grid: *const u8, grid_len: usize) -> *mut ResultBuffer {
+    let data = unsafe { std::slice::from_raw_parts(data, len) };
+    let grid = unsafe { std::slice::from_raw_parts(grid, grid_len) };
+    into_result_buffer(json::next_hint(data, grid))
+}
+
+#[no_mangle]
+extern "C" fn check_grid(data: *const u8, len: usize, grid: *const u8, grid_len: usize) -> *mut ResultBuffer {
+    let data = unsafe { std::slice::from_raw_parts(data, len) };
+    let grid = unsafe { std::slice::from_raw_parts(grid, grid_len) };
+    into_result_buffer(json::check_grid(data, grid))
+}
+
+#[no_mangle]
+extern "C" fn minimize_clues(data: *const u8, len: usize) -> *mut ResultBuffer {
+    let data = unsafe { std::slice::from_raw_parts(data, len) };
+    into_result_buffer(json::minimize_clues(data))
+}
+
+#[no_mangle]
+extern "C" fn redundant_rules(data: *const u8, len: usize) -> *mut ResultBuffer {
+    let data = unsafe { std::slice::from_raw_parts(data, len) };
+    into_result_buffer(json::redundant_rules(data))
+}
+
+#[no_mangle]
+extern "C" fn rate_difficulty(data: *const u8, len: usize) -> *mut ResultBuffer {
+    let data = unsafe { std::slice::from_raw_parts(data, len) };
+    into_result_buffer(json::rate_difficulty(data))
+}
+
+#[no_mangle]
+extern "C" fn explain_contradiction(data: *const u8, len: usize) -> *mut ResultBuffer {
+    let data = unsafe { std::slice::from_raw_parts(data, len) };
+    into_result_buffer(json::explain_contradiction(data))
+}
+
+#[no_mangle]
+extern "C" fn verify_grid(data: *const u8, len: usize, grid: *const u8, grid_len: usize) -> *mut ResultBuffer {
+    let data = unsafe { std::slice::from_raw_parts(data, len) };
+    let grid = unsafe { std::slice::from_raw_parts(grid, grid_len) };
+    into_result_buffer(json::verify_grid(data, grid))
+}
+
+#[no_mangle]
+extern "C" fn generate_puzzle(data: *const u8, len: usize) -> *mut ResultBuffer {
+    let data = unsafe { std::slice::from_raw_parts(data, len) };
+    into_result_buffer(json::generate_puzzle(data))
+}
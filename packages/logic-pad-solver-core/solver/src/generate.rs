@@ -0,0 +1,420 @@
+//! Random generation of puzzles with a unique solution.
+//!
+//! A random solution is drawn first, and then clues which hold in it are added one by one, each chosen so that
+//! it rules out another solution of the puzzle so far, until the solution is unique. At most one symbol is placed
+//! on each cell (or edge or corner, for lotuses and galaxies), including the symbols given in the rules.
+
+use std::ops::RangeInclusive;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::SolverError;
+use crate::puzzle::{
+    add_symbol, AreaNumberTile, Color, DartTile, GalaxyTile, LetterTile, LotusTile, MinesweeperTile, Orientation,
+    Puzzle, Rule, Tile, ViewpointTile, CURRENT_VERSION,
+};
+use crate::solver::{self, Board, Uniqueness};
+use crate::validate;
+use crate::verify::{self, is_symmetric, opposite, ray, Regions};
+
+/// A kind of symbols which may be placed as clues. The names are the same as the `type` of [`Rule`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum SymbolKind {
+    #[serde(rename = "minesweeper")]
+    Minesweeper,
+    #[serde(rename = "number")]
+    AreaNumber,
+    #[serde(rename = "letter")]
+    Letter,
+    #[serde(rename = "dart")]
+    Dart,
+    #[serde(rename = "viewpoint")]
+    Viewpoint,
+    #[serde(rename = "lotus")]
+    Lotus,
+    #[serde(rename = "galaxy")]
+    Galaxy,
+}
+
+/// The input of [`generate`]. `rules` are kept as they are in the generated puzzle, and the same `seed` always
+/// yields the same puzzle.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GeneratorConfig {
+    pub width: usize,
+    pub height: usize,
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+    #[serde(default)]
+    pub symbols: Vec<SymbolKind>,
+    #[serde(default)]
+    pub seed: u64,
+}
+
+/// A generated puzzle together with its unique solution.
+#[derive(Debug, Clone, Serialize)]
+pub struct Generated {
+    pub puzzle: Puzzle,
+    pub solution: Board,
+}
+
+// xorshift64, which is good enough for picking clues and keeps the output stable across platforms.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        let state = seed ^ 0x9e37_79b9_7f4a_7c15;
+        Rng(if state == 0 { 0x9e37_79b9_7f4a_7c15 } else { state })
+    }
+
+    fn next(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+const ORIENTATIONS: [Orientation; 8] = [
+    Orientation::Up,
+    Orientation::UpRight,
+    Orientation::Right,
+    Orientation::DownRight,
+    Orientation::Down,
+    Orientation::DownLeft,
+    Orientation::Left,
+    Orientation::UpLeft,
+];
+
+// Draws a random solution of `puzzle` by fixing random colors to the half of the cells in a random order,
+// backtracking on a cell only when its color makes the puzzle unsolvable.
+fn random_solution(puzzle: &Puzzle, rng: &mut Rng) -> Result<Option<Board>, SolverError> {
+    let mut puzzle = puzzle.clone();
+    let mut solution = match solver::solve(&puzzle, false)? {
+        Some(solution) => solution,
+        None => return Ok(None),
+    };
+
+    let mut cells = vec![];
+    for y in 0..puzzle.height {
+        for x in 0..puzzle.width {
+            cells.push((y, x));
+        }
+    }
+    rng.shuffle(&mut cells);
+
+    for &(y, x) in &cells[..cells.len() / 2] {
+        let color = if rng.below(2) == 0 { Color::White } else { Color::Black };
+        puzzle.tiles[y][x] = Tile { exists: true, fixed: true, color };
+        if solution[y][x] == Some(color) {
+            continue;
+        }
+        match solver::solve(&puzzle, false)? {
+            Some(s) => solution = s,
+            None => puzzle.tiles[y][x].color = opposite(color),
+        }
+    }
+
+    Ok(Some(solution))
+}
+
+// The number on a clue whose actual value is `value`, taking offByX into account.
+// Returns `None` if no number in `valid` fits.
+fn clue_number(value: i32, valid: RangeInclusive<i32>, off_by: Option<i32>, rng: &mut Rng) -> Option<i32> {
+    let numbers = match off_by {
+        Some(off_by) => vec![value - off_by, value + off_by],
+        None => vec![value],
+    };
+    let numbers = numbers.into_iter().filter(|n| valid.contains(n)).collect::<Vec<_>>();
+    if numbers.is_empty() {
+        None
+    } else {
+        Some(numbers[rng.below(numbers.len())])
+    }
+}
+
+// Positions of the symbols in `rules`, in doubled coordinates.
+fn symbol_positions(rules: &[Rule]) -> Vec<(usize, usize)> {
+    let mut positions = vec![];
+    for rule in rules {
+        match rule {
+            Rule::Minesweeper { tiles } => positions.extend(tiles.iter().map(|t| (t.y * 2, t.x * 2))),
+            Rule::AreaNumber { tiles } => positions.extend(tiles.iter().map(|t| (t.y * 2, t.x * 2))),
+            Rule::Letter { tiles } => positions.extend(tiles.iter().map(|t| (t.y * 2, t.x * 2))),
+            Rule::Dart { tiles } => positions.extend(tiles.iter().map(|t| (t.y * 2, t.x * 2))),
+            Rule::Viewpoint { tiles } => positions.extend(tiles.iter().map(|t| (t.y * 2, t.x * 2))),
+            Rule::Lotus { tiles } => positions.extend(tiles.iter().map(|t| (t.y, t.x))),
+            Rule::Galaxy { tiles } => positions.extend(tiles.iter().map(|t| (t.y, t.x))),
+            _ => (),
+        }
+    }
+    positions
+}
+
+// Lists the symbols which are satisfied by `solution` on their own, except on the positions in `occupied`.
+fn candidate_clues(
+    solution: &Board,
+    symbols: &[SymbolKind],
+    off_by: Option<i32>,
+    occupied: &[(usize, usize)],
+    rng: &mut Rng,
+) -> Vec<Symbol> {
+    let height = solution.len();
+    let width = solution[0].len();
+    let regions = Regions::new(solution);
+    let mut clues = vec![];
+
+    for &kind in symbols {
+        for y in 0..height {
+            for x in 0..width {
+                let color = match solution[y][x] {
+                    Some(color) => color,
+                    None => continue,
+                };
+                match kind {
+                    SymbolKind::Minesweeper => {
+                        let n = solution[y.saturating_sub(1)..(y + 2).min(height)]
+                            .iter()
+                            .flat_map(|row| &row[x.saturating_sub(1)..(x + 2).min(width)])
+                            .filter(|&&cell| cell == Some(opposite(color)))
+                            .count();
+                        if let Some(number) = clue_number(n as i32, 0..=8, off_by, rng) {
+                            clues.push(Symbol::Minesweeper(MinesweeperTile { y, x, number }));
+                        }
+                    }
+                    SymbolKind::AreaNumber => {
+                        let size = regions.cells[regions.id[y][x].unwrap()].len() as i32;
+                        if let Some(number) = clue_number(size, 1..=i32::MAX, off_by, rng) {
+                            clues.push(Symbol::AreaNumber(AreaNumberTile { y, x, number }));
+                        }
+                    }
+                    SymbolKind::Letter => {
+                        let r = regions.id[y][x].unwrap();
+                        if r < 26 {
+                            let letter = ((b'A' + r as u8) as char).to_string();
+                            clues.push(Symbol::Letter(LetterTile { y, x, letter }));
+                        }
+                    }
+                    SymbolKind::Dart => {
+                        for orientation in ORIENTATIONS {
+                            let n = ray(y, x, height, width, orientation)
+                                .into_iter()
+                                .filter(|&(y, x)| solution[y][x] == Some(opposite(color)))
+                                .count();
+                            if let Some(number) = clue_number(n as i32, 0..=i32::MAX, off_by, rng) {
+                                clues.push(Symbol::Dart(DartTile { y, x, orientation, number }));
+                            }
+                        }
+                    }
+                    SymbolKind::Viewpoint => {
+                        let mut n = 1;
+                        for d in [Orientation::Up, Orientation::Down, Orientation::Left, Orientation::Right] {
+                            n += ray(y, x, height, width, d)
+                                .into_iter()
+                                .take_while(|&(y, x)| solution[y][x] == Some(color))
+                                .count();
+                        }
+                        if let Some(number) = clue_number(n as i32, 1..=i32::MAX, off_by, rng) {
+                            clues.push(Symbol::Viewpoint(ViewpointTile { y, x, number }));
+                        }
+                    }
+                    SymbolKind::Lotus | SymbolKind::Galaxy => (),
+                }
+            }
+        }
+
+        // Lotuses and galaxies may also be placed on edges and corners, so they are enumerated in doubled coordinates.
+        for sy in 0..(height * 2 - 1) {
+            for sx in 0..(width * 2 - 1) {
+                match kind {
+                    SymbolKind::Lotus => {
+                        for orientation in ORIENTATIONS {
+                            // Positions rejected by validation are skipped.
+                            let valid = match orientation {
+                                Orientation::Up | Orientation::Down => sy % 2 == 0,
+                                Orientation::Left | Orientation::Right => sx % 2 == 0,
+                                _ => sy % 2 == 0 && sx % 2 == 0,
+                            };
+                            if valid && is_symmetric(&regions, sy, sx, Some(orientation)) {
+                                clues.push(Symbol::Lotus(LotusTile { y: sy, x: sx, orientation }));
+                            }
+                        }
+                    }
+                    SymbolKind::Galaxy if (sy % 2 == 0 || sx % 2 == 0) && is_symmetric(&regions, sy, sx, None) => {
+                        clues.push(Symbol::Galaxy(GalaxyTile { y: sy, x: sx }));
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    clues.retain(|clue| !occupied.contains(&clue.position()));
+    clues
+}
+
+// A symbol to be added as a clue.
+#[derive(Clone)]
+enum Symbol {
+    Minesweeper(MinesweeperTile),
+    AreaNumber(AreaNumberTile),
+    Letter(LetterTile),
+    Dart(DartTile),
+    Viewpoint(ViewpointTile),
+    Lotus(LotusTile),
+    Galaxy(GalaxyTile),
+}
+
+impl Symbol {
+    // The position in doubled coordinates.
+    fn position(&self) -> (usize, usize) {
+        match self {
+            Symbol::Minesweeper(t) => (t.y * 2, t.x * 2),
+            Symbol::AreaNumber(t) => (t.y * 2, t.x * 2),
+            Symbol::Letter(t) => (t.y * 2, t.x * 2),
+            Symbol::Dart(t) => (t.y * 2, t.x * 2),
+            Symbol::Viewpoint(t) => (t.y * 2, t.x * 2),
+            Symbol::Lotus(t) => (t.y, t.x),
+            Symbol::Galaxy(t) => (t.y, t.x),
+        }
+    }
+}
+
+fn add_clue(rules: &mut Vec<Rule>, symbol: Symbol) {
+    match symbol {
+        Symbol::Minesweeper(tile) => add_symbol(
+            rules,
+            tile,
+            |r| if let Rule::Minesweeper { tiles } = r { Some(tiles) } else { None },
+            |tiles| Rule::Minesweeper { tiles },
+        ),
+        Symbol::AreaNumber(tile) => add_symbol(
+            rules,
+            tile,
+            |r| if let Rule::AreaNumber { tiles } = r { Some(tiles) } else { None },
+            |tiles| Rule::AreaNumber { tiles },
+        ),
+        Symbol::Letter(tile) => add_symbol(
+            rules,
+            tile,
+            |r| if let Rule::Letter { tiles } = r { Some(tiles) } else { None },
+            |tiles| Rule::Letter { tiles },
+        ),
+        Symbol::Dart(tile) => add_symbol(
+            rules,
+            tile,
+            |r| if let Rule::Dart { tiles } = r { Some(tiles) } else { None },
+            |tiles| Rule::Dart { tiles },
+        ),
+        Symbol::Viewpoint(tile) => add_symbol(
+            rules,
+            tile,
+            |r| if let Rule::Viewpoint { tiles } = r { Some(tiles) } else { None },
+            |tiles| Rule::Viewpoint { tiles },
+        ),
+        Symbol::Lotus(tile) => add_symbol(
+            rules,
+            tile,
+            |r| if let Rule::Lotus { tiles } = r { Some(tiles) } else { None },
+            |tiles| Rule::Lotus { tiles },
+        ),
+        Symbol::Galaxy(tile) => add_symbol(
+            rules,
+            tile,
+            |r| if let Rule::Galaxy { tiles } = r { Some(tiles) } else { None },
+            |tiles| Rule::Galaxy { tiles },
+        ),
+    }
+}
+
+// Whether `grid` satisfies `puzzle`. A puzzle which fails validation is satisfied by nothing.
+fn satisfies(puzzle: &Puzzle, grid: &Board) -> bool {
+    matches!(verify::verify(puzzle, grid), Ok(violations) if violations.is_empty())
+}
+
+/// Generates a random puzzle of the given size with the rules in `config`, whose clues are symbols of the kinds
+/// in `config.symbols` and, only where no symbol helps, fixed tiles. Returns `None` if the rules have no solution.
+///
+/// Every tile of the generated puzzle exists.
+pub fn generate(config: &GeneratorConfig) -> Result<Option<Generated>, SolverError> {
+    let mut rng = Rng::new(config.seed);
+    let mut puzzle = Puzzle {
+        version: CURRENT_VERSION,
+        width: config.width,
+        height: config.height,
+        tiles: vec![vec![Tile { exists: true, fixed: false, color: Color::Undecided }; config.width]; config.height],
+        rules: config.rules.clone(),
+        connections: vec![],
+    };
+    validate::check(&puzzle)?;
+
+    let solution = match random_solution(&puzzle, &mut rng)? {
+        Some(solution) => solution,
+        None => return Ok(None),
+    };
+
+    let off_by = config.rules.iter().find_map(|rule| match *rule {
+        Rule::OffByX { number } if number > 0 => Some(number),
+        _ => None,
+    });
+    let occupied = symbol_positions(&config.rules);
+    let mut candidates = candidate_clues(&solution, &config.symbols, off_by, &occupied, &mut rng);
+    rng.shuffle(&mut candidates);
+
+    loop {
+        let other = match solver::check_uniqueness(&puzzle)? {
+            Uniqueness::Unique { solution: unique } if unique == solution => break,
+            // Only reachable if the solver disagrees with `verify` on a clue; the other solution is ruled out
+            // like any other.
+            Uniqueness::Unique { solution: other } => other,
+            Uniqueness::Multiple { solutions } => solutions.into_iter().find(|s| *s != solution).unwrap(),
+            // Every clue is satisfied by the solution, so this is also a disagreement with `verify`.
+            Uniqueness::None => return Err(SolverError::InconsistentSolver),
+        };
+
+        // The first candidate which rules out `other` and keeps `solution`
+        let mut found = None;
+        for (i, clue) in candidates.iter().enumerate() {
+            let mut next = puzzle.clone();
+            add_clue(&mut next.rules, clue.clone());
+            if !satisfies(&next, &other) && satisfies(&next, &solution) {
+                found = Some((i, next));
+                break;
+            }
+        }
+
+        match found {
+            Some((i, next)) => {
+                let position = candidates.remove(i).position();
+                candidates.retain(|clue| clue.position() != position);
+                puzzle = next;
+            }
+            None => {
+                let mut diffs = vec![];
+                for y in 0..puzzle.height {
+                    for x in 0..puzzle.width {
+                        if solution[y][x] != other[y][x] {
+                            diffs.push((y, x));
+                        }
+                    }
+                }
+                let (y, x) = diffs[rng.below(diffs.len())];
+                puzzle.tiles[y][x] = Tile { exists: true, fixed: true, color: solution[y][x].unwrap() };
+            }
+        }
+    }
+
+    Ok(Some(Generated { puzzle, solution }))
+}
+
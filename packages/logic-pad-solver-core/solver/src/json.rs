@@ -0,0 +1,144 @@
+// The JSON interface shared by the C ABI exports (`ffi.rs`) and the wasm-bindgen exports (`wasm.rs`).
+// Every function takes a JSON-encoded `Puzzle` and returns the JSON-encoded result or an error object.
+
+use serde::Serialize;
+
+use crate::error::SolverError;
+use crate::{difficulty, explain, generate, puzzle, solver, validate, verify};
+
+#[derive(Serialize)]
+struct ErrorResponse<'a> {
+    error: String,
+    #[serde(flatten)]
+    detail: &'a SolverError,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    problems: Option<Vec<ErrorResponse<'a>>>,
+}
+
+impl<'a> ErrorResponse<'a> {
+    fn new(e: &'a SolverError) -> ErrorResponse<'a> {
+        ErrorResponse {
+            error: e.to_string(),
+            detail: e,
+            problems: None,
+        }
+    }
+}
+
+fn error_response(e: &SolverError) -> String {
+    serde_json::to_string(&ErrorResponse::new(e)).unwrap()
+}
+
+// The first problem is reported at the top level, and `problems` lists all of them.
+fn validation_error_response(problems: &[SolverError]) -> String {
+    let mut response = ErrorResponse::new(&problems[0]);
+    response.problems = Some(problems.iter().map(ErrorResponse::new).collect());
+    serde_json::to_string(&response).unwrap()
+}
+
+fn parse_json<T: serde::de::DeserializeOwned>(data: &[u8]) -> Result<T, SolverError> {
+    serde_json::from_slice(data).map_err(|e| SolverError::InvalidJson { message: e.to_string() })
+}
+
+fn run_on_puzzle<T: Serialize>(data: &[u8], f: impl FnOnce(&puzzle::Puzzle) -> Result<T, SolverError>) -> String {
+    let puzzle = match parse_json(data).and_then(puzzle::Puzzle::from_json_value) {
+        Ok(puzzle) => puzzle,
+        Err(e) => return error_response(&e),
+    };
+
+    let problems = validate::validate(&puzzle);
+    if !problems.is_empty() {
+        return validation_error_response(&problems);
+    }
+
+    match f(&puzzle) {
+        Ok(result) => serde_json::to_string(&result).unwrap(),
+        Err(e) => error_response(&e),
+    }
+}
+
+pub fn solve(data: &[u8], underclued: bool) -> String {
+    run_on_puzzle(data, |puzzle| solver::solve(puzzle, underclued))
+}
+
+pub fn solve_with_budget(data: &[u8], budget: &solver::Budget) -> String {
+    run_on_puzzle(data, |puzzle| solver::solve_with_budget(puzzle, budget))
+}
+
+#[derive(Serialize)]
+struct Fact {
+    y: usize,
+    x: usize,
+    color: puzzle::Color,
+}
+
+// `on_fact` receives each proven cell as JSON.
+pub fn solve_with_progress(data: &[u8], budget: &solver::Budget, mut on_fact: impl FnMut(String)) -> String {
+    run_on_puzzle(data, |puzzle| {
+        solver::solve_with_progress(puzzle, budget, |y, x, color| {
+            on_fact(serde_json::to_string(&Fact { y, x, color }).unwrap())
+        })
+    })
+}
+
+pub fn enumerate_solutions(data: &[u8], limit: usize) -> String {
+    run_on_puzzle(data, |puzzle| solver::enumerate_solutions(puzzle, limit))
+}
+
+pub fn check_uniqueness(data: &[u8]) -> String {
+    run_on_puzzle(data, solver::check_uniqueness)
+}
+
+pub fn count_solutions(data: &[u8], cap: usize) -> String {
+    run_on_puzzle(data, |puzzle| solver::count_solutions(puzzle, cap))
+}
+
+pub fn next_hint(data: &[u8], grid: &[u8]) -> String {
+    run_on_puzzle(data, |puzzle| {
+        let grid: Vec<Vec<puzzle::Color>> = parse_json(grid)?;
+        explain::next_hint(puzzle, &grid)
+    })
+}
+
+pub fn check_grid(data: &[u8], grid: &[u8]) -> String {
+    run_on_puzzle(data, |puzzle| {
+        let grid: Vec<Vec<puzzle::Color>> = parse_json(grid)?;
+        explain::check_grid(puzzle, &grid)
+    })
+}
+
+pub fn minimize_clues(data: &[u8]) -> String {
+    run_on_puzzle(data, explain::minimize_clues)
+}
+
+pub fn redundant_rules(data: &[u8]) -> String {
+    run_on_puzzle(data, explain::redundant_rules)
+}
+
+pub fn rate_difficulty(data: &[u8]) -> String {
+    run_on_puzzle(data, difficulty::rate_difficulty)
+}
+
+pub fn explain_contradiction(data: &[u8]) -> String {
+    run_on_puzzle(data, explain::explain_contradiction)
+}
+
+pub fn verify_grid(data: &[u8], grid: &[u8]) -> String {
+    run_on_puzzle(data, |puzzle| {
+        let grid: solver::Board = parse_json(grid)?;
+        verify::verify(puzzle, &grid)
+    })
+}
+
+// Unlike the other functions, `data` is a `GeneratorConfig` rather than a puzzle.
+pub fn generate_puzzle(data: &[u8]) -> String {
+    let config: generate::GeneratorConfig = match parse_json(data) {
+        Ok(config) => config,
+        Err(e) => return error_response(&e),
+    };
+
+    match generate::generate(&config) {
+        Ok(result) => serde_json::to_string(&result).unwrap(),
+        Err(e) => error_response(&e),
+    }
+}
@@ -1,42 +1,47 @@
-use serde_json;
+//! A solver for [Logic Pad](https://logic-pad.com) puzzles based on cspuz.
+//!
+//! A puzzle is described by [`Puzzle`], which has the same JSON representation as the input of the
+//! JavaScript package (`PuzzleData` in `solverBridge.d.ts`). Puzzles are solved by the functions in [`solver`]:
+//!
+//! ```no_run
+//! let puzzle: solver::Puzzle = serde_json::from_str(r#"{
+//!     "width": 2, "height": 1, "connections": [],
+//!     "tiles": [[{"exists": true, "fixed": true, "color": "light"}, {"exists": true, "fixed": false, "color": "gray"}]],
+//!     "rules": [{"type": "cellCount", "color": "dark", "count": 1}]
+//! }"#).unwrap();
+//!
+//! let board = solver::solve(&puzzle, false).unwrap().unwrap();
+//! assert_eq!(board[0][1], Some(solver::Color::Black));
+//! ```
+//!
+//! Puzzles and boards can also be written in the plain-text notation of [`text`], and puzzles can be converted
+//! from and to Logic Pad share links by [`url`].
+//!
+//! The crate is also built as a `cdylib` whose C ABI exports are used by the emscripten build. With the
+//! `wasm-bindgen` feature, it also provides wasm-bindgen exports for the wasm32-unknown-unknown target.
 
-mod puzzle;
+pub mod difficulty;
+pub mod error;
+pub mod explain;
+mod ffi;
+pub mod generate;
+mod json;
+pub mod puzzle;
 mod shapes;
-mod solver;
+pub mod solver;
+pub mod text;
+pub mod url;
+pub mod validate;
+pub mod verify;
+#[cfg(feature = "wasm-bindgen")]
+mod wasm;
 
-static mut SHARED_ARRAY: Vec<u8> = Vec::new();
-
-fn solve_puzzle_impl(data: &[u8], underclued: bool) -> String {
-    let puzzle: puzzle::Puzzle = match serde_json::from_slice(data) {
-        Ok(p) => p,
-        Err(e) => {
-            return format!("{{\"error\": \"{}\"}}", e);
-        }
-    };
-
-    let solution = match solver::solve(&puzzle, underclued) {
-        Ok(solution) => solution,
-        Err(e) => {
-            return format!("{{\"error\": \"{}\"}}", e);
-        }
-    };
-
-    serde_json::to_string(&solution).unwrap()
-}
-
-#[no_mangle]
-fn solve_puzzle(data: *const u8, len: usize, underclued: i32) -> *const u8 {
-    let data = unsafe { std::slice::from_raw_parts(data, len) };
-    let result = solve_puzzle_impl(data, underclued != 0);
-
-    unsafe {
-        let result_len = result.len();
-        SHARED_ARRAY.clear();
-        SHARED_ARRAY.push((result_len & 0xff) as u8);
-        SHARED_ARRAY.push(((result_len >> 8) & 0xff) as u8);
-        SHARED_ARRAY.push(((result_len >> 16) & 0xff) as u8);
-        SHARED_ARRAY.push(((result_len >> 24) & 0xff) as u8);
-        SHARED_ARRAY.extend(result.as_bytes());
-        SHARED_ARRAY.as_ptr()
-    }
-}
+pub use error::SolverError;
+pub use generate::{generate, GeneratorConfig};
+pub use puzzle::{Color, Puzzle, Rule};
+pub use solver::{
+    check_uniqueness, count_solutions, enumerate_solutions, solve, solve_with_budget, solve_with_progress, Board, Budget, BudgetedSolution,
+    SolutionCount, Uniqueness,
+};
+pub use validate::validate;
+pub use verify::{verify, Violation};
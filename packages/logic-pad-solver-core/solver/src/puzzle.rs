@@ -1,6 +1,18 @@
+//! The puzzle model, deserialized from and serialized to the same JSON as `PuzzleData` in `solverBridge.d.ts`.
+//!
+//! Serialization is stable: fields are written in declaration order and rules, symbols and connections in their
+//! order in the model, and reading the output back gives an equal model.
+//!
+//! The format is versioned by the `version` field of [`Puzzle`]. [`Puzzle::from_json`] migrates older versions
+//! to [`CURRENT_VERSION`], and with the `schema` feature, [`json_schema`] describes the current version.
+
 use serde::{Deserialize, Serialize};
 
+use crate::error::SolverError;
+
+/// A tile color. `Undecided` is called "gray" in Logic Pad.
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum Color {
     #[serde(rename = "gray")]
     Undecided,
@@ -11,6 +23,7 @@ pub enum Color {
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum Orientation {
     #[serde(rename = "left")]
     Left,
@@ -30,35 +43,41 @@ pub enum Orientation {
     DownLeft,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// A tile of the board. `color` is taken into account only if `fixed` is true.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Tile {
     pub exists: bool,
     pub fixed: bool,
     pub color: Color,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct MinesweeperTile {
     pub y: usize,
     pub x: usize,
     pub number: i32,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AreaNumberTile {
     pub y: usize,
     pub x: usize,
     pub number: i32,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct LetterTile {
     pub y: usize,
     pub x: usize,
     pub letter: String,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DartTile {
     pub y: usize,
     pub x: usize,
@@ -66,27 +85,33 @@ pub struct DartTile {
     pub number: i32,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ViewpointTile {
     pub y: usize,
     pub x: usize,
     pub number: i32,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// A lotus symbol. The coordinates are doubled so that symbols on edges and corners can be represented.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct LotusTile {
     pub y: usize,
     pub x: usize,
     pub orientation: Orientation,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// A galaxy symbol. The coordinates are doubled so that symbols on edges and corners can be represented.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct GalaxyTile {
     pub y: usize,
     pub x: usize,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum SymbolCountKind {
     #[serde(rename = "atMost")]
     AtMost,
@@ -96,7 +121,9 @@ pub enum SymbolCountKind {
     Exactly,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// A global rule or a set of symbols of the same kind.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 pub enum Rule {
     #[serde(rename = "connectAll")]
@@ -129,9 +156,14 @@ pub enum Rule {
     OffByX { number: i32 },
     #[serde(rename = "symbolCount")]
     SymbolCount { number: i32, kind: SymbolCountKind, color: Color },
+    /// Exactly `number` symbols of the puzzle are false, and all other symbols are true.
+    #[serde(rename = "lyingSymbols")]
+    LyingSymbols { number: i32 },
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// Merges the tiles at `(y1, x1)` and `(y2, x2)` so that they have the same color.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Connection {
     pub y1: usize,
     pub x1: usize,
@@ -139,11 +171,87 @@ pub struct Connection {
     pub x2: usize,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// The version of the JSON format of [`Puzzle`] which this crate reads and writes.
+///
+/// Version 0 is the format before `version` was added, which is read as if it were version 1.
+pub const CURRENT_VERSION: u64 = 1;
+
+/// A puzzle. `tiles` is indexed by `[y][x]` and must have `height` rows of `width` tiles.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Puzzle {
+    /// The version of the format, which is the current version (1) when written and omitted in version 0.
+    #[serde(default)]
+    pub version: u64,
     pub width: usize,
     pub height: usize,
     pub tiles: Vec<Vec<Tile>>,
     pub rules: Vec<Rule>,
     pub connections: Vec<Connection>,
 }
+
+fn invalid_json(e: serde_json::Error) -> SolverError {
+    SolverError::InvalidJson { message: e.to_string() }
+}
+
+// Rewrites the JSON of a puzzle of an older version into the current version.
+fn migrate(value: &mut serde_json::Value) -> Result<(), SolverError> {
+    let version = match value.get("version") {
+        None => 0,
+        Some(version) => version.as_u64().ok_or_else(|| SolverError::InvalidJson {
+            message: String::from("version must be a non-negative integer"),
+        })?,
+    };
+    if version > CURRENT_VERSION {
+        return Err(SolverError::UnsupportedVersion { version });
+    }
+
+    // Version 0 differs from version 1 only in the missing `version` field.
+    // Migrations of future versions go here, in ascending order.
+
+    if let serde_json::Value::Object(map) = value {
+        map.insert(String::from("version"), serde_json::Value::from(CURRENT_VERSION));
+    }
+    Ok(())
+}
+
+impl Puzzle {
+    /// Parses a puzzle from JSON of any supported version, migrating it to [`CURRENT_VERSION`].
+    /// The puzzle is not validated.
+    pub fn from_json(json: &str) -> Result<Puzzle, SolverError> {
+        Puzzle::from_json_value(serde_json::from_str(json).map_err(invalid_json)?)
+    }
+
+    /// Same as [`Puzzle::from_json`], but takes parsed JSON.
+    pub fn from_json_value(mut value: serde_json::Value) -> Result<Puzzle, SolverError> {
+        migrate(&mut value)?;
+        serde_json::from_value(value).map_err(invalid_json)
+    }
+
+    /// Writes the puzzle as JSON, which [`Puzzle::from_json`] reads back as an equal puzzle.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+/// The JSON Schema of the current version of [`Puzzle`].
+#[cfg(feature = "schema")]
+pub fn json_schema() -> String {
+    serde_json::to_string_pretty(&schemars::schema_for!(Puzzle)).unwrap()
+}
+
+// Adds a symbol to the rule of its kind, creating the rule if it does not exist yet.
+pub(crate) fn add_symbol<T>(
+    rules: &mut Vec<Rule>,
+    tile: T,
+    get: impl Fn(&mut Rule) -> Option<&mut Vec<T>>,
+    make: impl FnOnce(Vec<T>) -> Rule,
+) {
+    for rule in rules.iter_mut() {
+        if let Some(tiles) = get(rule) {
+            tiles.push(tile);
+            return;
+        }
+    }
+    rules.push(make(vec![tile]));
+}
@@ -1,8 +1,16 @@
+use crate::error::SolverError;
 use crate::puzzle::{AreaNumberTile, Color, Connection, DartTile, GalaxyTile, LetterTile, LotusTile, MinesweeperTile, Orientation, Puzzle, Rule, SymbolCountKind, Tile, ViewpointTile};
 
-use cspuz_rs::solver::{all, int_constant, BoolVarArray2D, Solver, count_true, consecutive_prefix_true};
+use serde::Serialize;
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Instant;
+use std::time::Duration;
+
+use cspuz_rs::solver::{all, any, int_constant, BoolExpr, BoolVar, BoolVarArray2D, Solver, count_true, consecutive_prefix_true};
 use cspuz_rs::graph;
 use crate::shapes::{ConstraintType, ShapesConstraint};
+use crate::validate;
 
 fn rotate_pattern(pattern: &[Vec<Color>]) -> Vec<Vec<Color>> {
     let height = pattern.len();
@@ -50,6 +58,127 @@ fn enumerate_patterns(pattern: &[Vec<Color>]) -> Vec<Vec<Vec<Color>>> {
     patterns
 }
 
+/// A coloring of the board indexed by `[y][x]`. `None` stands for a non-existing or undetermined cell.
+pub type Board = Vec<Vec<Option<Color>>>;
+
+/// The result of [`check_uniqueness`]. In the `Multiple` case, the two solutions differ in at least one cell.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status")]
+pub enum Uniqueness {
+    #[serde(rename = "none")]
+    None,
+    #[serde(rename = "unique")]
+    Unique { solution: Board },
+    #[serde(rename = "multiple")]
+    Multiple { solutions: [Board; 2] },
+}
+
+/// The result of [`count_solutions`]. `exact` is false when there are more than `count` (= cap) solutions.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct SolutionCount {
+    pub count: usize,
+    pub exact: bool,
+}
+
+/// Limits on the work done by [`solve_with_budget`]. `None` means no limit.
+///
+/// The budget is checked between solver calls, so a single call may still overrun `time_limit`.
+/// On wasm32-unknown-unknown, `time_limit` needs the `wasm-bindgen` feature, which reads the clock of the JS host;
+/// without it, a `time_limit` is rejected with [`SolverError::UnsupportedTimeLimit`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Budget {
+    pub time_limit: Option<Duration>,
+    pub max_solver_calls: Option<usize>,
+}
+
+/// The result of [`solve_with_budget`]. `facts` contains the cells whose colors are common to all solutions,
+/// as in [`solve`] with `underclued`; on `Timeout`, only the cells proven before the budget ran out are filled.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status")]
+pub enum BudgetedSolution {
+    #[serde(rename = "none")]
+    None,
+    #[serde(rename = "solved")]
+    Solved { facts: Board },
+    #[serde(rename = "timeout")]
+    Timeout { facts: Board },
+}
+
+// Measures the time elapsed since its creation, if a clock is available on this target.
+struct Stopwatch {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    start: Instant,
+    // Milliseconds since the epoch, from `Date.now()`
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown", feature = "wasm-bindgen"))]
+    start: f64,
+}
+
+impl Stopwatch {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    fn start() -> Option<Stopwatch> {
+        Some(Stopwatch { start: Instant::now() })
+    }
+
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown", feature = "wasm-bindgen"))]
+    fn start() -> Option<Stopwatch> {
+        Some(Stopwatch { start: js_sys::Date::now() })
+    }
+
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown", feature = "wasm-bindgen"))]
+    fn elapsed(&self) -> Duration {
+        // `Date.now()` may go backwards if the system clock is adjusted.
+        Duration::from_secs_f64((js_sys::Date::now() - self.start).max(0.0) / 1000.0)
+    }
+
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown", not(feature = "wasm-bindgen")))]
+    fn start() -> Option<Stopwatch> {
+        None
+    }
+
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown", not(feature = "wasm-bindgen")))]
+    fn elapsed(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+struct BudgetTracker {
+    calls: usize,
+    max_calls: Option<usize>,
+    time_limit: Option<(Stopwatch, Duration)>,
+}
+
+impl BudgetTracker {
+    fn new(budget: &Budget) -> Result<BudgetTracker, SolverError> {
+        let time_limit = match budget.time_limit {
+            Some(limit) => Some((Stopwatch::start().ok_or(SolverError::UnsupportedTimeLimit)?, limit)),
+            None => None,
+        };
+        Ok(BudgetTracker { calls: 0, max_calls: budget.max_solver_calls, time_limit })
+    }
+
+    // Returns false if the budget is exhausted, and otherwise counts a solver call.
+    fn spend(&mut self) -> bool {
+        if let Some(max_calls) = self.max_calls {
+            if self.calls >= max_calls {
+                return false;
+            }
+        }
+        if let Some((stopwatch, limit)) = &self.time_limit {
+            if stopwatch.elapsed() >= *limit {
+                return false;
+            }
+        }
+
+        self.calls += 1;
+        true
+    }
+}
+
 struct LogicPadSolver<'a> {
     solver: Solver<'a>,
     height: usize,
@@ -57,10 +186,12 @@ struct LogicPadSolver<'a> {
     off_by: Option<i32>,
     is_white: BoolVarArray2D,
     is_black: BoolVarArray2D,
+    // Whether each symbol holds, if the puzzle has a lyingSymbols rule.
+    symbol_truths: Option<Vec<BoolVar>>,
 }
 
 impl<'a> LogicPadSolver<'a> {
-    fn new(height: usize, width: usize, off_by: Option<i32>) -> LogicPadSolver<'a> {
+    fn new(height: usize, width: usize, off_by: Option<i32>, lying_symbols: bool) -> LogicPadSolver<'a> {
         let mut solver = Solver::new();
         let is_white = solver.bool_var_2d((height, width));
         let is_black = solver.bool_var_2d((height, width));
@@ -74,17 +205,73 @@ impl<'a> LogicPadSolver<'a> {
             off_by,
             is_white,
             is_black,
+            symbol_truths: if lying_symbols { Some(vec![]) } else { None },
+        }
+    }
+
+    // Adds the constraint of a single symbol, which is the conjunction of `claims`.
+    // With a lyingSymbols rule, the constraint is tied to a new truth literal instead of being enforced.
+    fn add_symbol_claims(&mut self, claims: Vec<BoolExpr>) {
+        match &mut self.symbol_truths {
+            Some(truths) => {
+                let truth = self.solver.bool_var();
+                self.solver.add_expr(truth.iff(all(claims)));
+                truths.push(truth);
+            }
+            None => {
+                for claim in claims {
+                    self.solver.add_expr(claim);
+                }
+            }
         }
     }
 
-    fn add_tiles(&mut self, tiles: &[Vec<Tile>]) -> Result<(), &'static str> {
+    // Returns variables which are true exactly at the cells of the region containing `(y, x)`.
+    fn add_region(&mut self, y: usize, x: usize) -> BoolVarArray2D {
         let height = self.height;
         let width = self.width;
 
-        assert_eq!(tiles.len(), height);
+        let block_cells = self.solver.bool_var_2d((height, width));
+        graph::active_vertices_connected_2d(&mut self.solver, &block_cells);
+        self.solver.add_expr(block_cells.at((y, x)));
 
+        let mut adj_pairs = vec![];
         for y in 0..height {
-            assert_eq!(tiles[y].len(), width);
+            for x in 0..width {
+                if y > 0 {
+                    adj_pairs.push(((y, x), (y - 1, x)));
+                }
+                if x > 0 {
+                    adj_pairs.push(((y, x), (y, x - 1)));
+                }
+            }
+        }
+        for (p, q) in adj_pairs {
+            self.solver.add_expr(
+                (self.is_black.at(p) & self.is_black.at(q)).imp(block_cells.at(p).iff(block_cells.at(q)))
+            );
+            self.solver.add_expr(
+                (self.is_white.at(p) & self.is_white.at(q)).imp(block_cells.at(p).iff(block_cells.at(q)))
+            );
+            self.solver.add_expr(
+                (self.is_black.at(p) ^ self.is_black.at(q)).imp(!block_cells.at(p) | !block_cells.at(q))
+            );
+            self.solver.add_expr(
+                (self.is_white.at(p) ^ self.is_white.at(q)).imp(!block_cells.at(p) | !block_cells.at(q))
+            );
+        }
+
+        block_cells
+    }
+
+    fn add_tiles(&mut self, tiles: &[Vec<Tile>]) -> Result<(), SolverError> {
+        let height = self.height;
+        let width = self.width;
+
+        debug_assert_eq!(tiles.len(), height);
+
+        for y in 0..height {
+            debug_assert_eq!(tiles[y].len(), width);
 
             for x in 0..width {
                 let tile = &tiles[y][x];
@@ -110,7 +297,7 @@ impl<'a> LogicPadSolver<'a> {
                             self.solver.add_expr(b);
                         }
                         Color::Undecided => {
-                            return Err("gray tile is fixed");
+                            return Err(SolverError::FixedGrayTile { y, x });
                         }
                     }
                 }
@@ -120,16 +307,16 @@ impl<'a> LogicPadSolver<'a> {
         Ok(())
     }
 
-    fn add_forbidden_pattern(&mut self, pattern: &[Vec<Tile>]) -> Result<(), &'static str> {
+    fn add_forbidden_pattern(&mut self, rule: usize, pattern: &[Vec<Tile>]) -> Result<(), SolverError> {
         let height = self.height;
         let width = self.width;
 
         let p_height = pattern.len();
-        assert!(p_height > 0);
+        debug_assert!(p_height > 0);
         let p_width = pattern[0].len();
 
         for y in 0..p_height {
-            assert_eq!(pattern[y].len(), p_width);
+            debug_assert_eq!(pattern[y].len(), p_width);
         }
 
         let mut ymin = p_height;
@@ -149,7 +336,7 @@ impl<'a> LogicPadSolver<'a> {
         }
 
         if ymin > ymax {
-            return Err("empty forbidden pattern");
+            return Err(SolverError::EmptyForbiddenPattern { rule });
         }
 
         let pattern = {
@@ -198,7 +385,7 @@ impl<'a> LogicPadSolver<'a> {
         Ok(())
     }
 
-    fn add_minesweeper(&mut self, tiles: &[MinesweeperTile]) -> Result<(), &'static str> {
+    fn add_minesweeper(&mut self, tiles: &[MinesweeperTile]) -> Result<(), SolverError> {
         let height = self.height;
         let width = self.width;
 
@@ -212,23 +399,28 @@ impl<'a> LogicPadSolver<'a> {
             let xmin = if x > 0 { x - 1 } else { 0 };
             let xmax = (x + 2).min(width);
 
-            if let Some(off_by) = self.off_by {
-                self.solver.add_expr(self.is_white.at((y, x)).imp(self.is_black.slice((ymin..ymax, xmin..xmax)).count_true().eq(num + off_by) | self.is_black.slice((ymin..ymax, xmin..xmax)).count_true().eq(num - off_by)));
-                self.solver.add_expr(self.is_black.at((y, x)).imp(self.is_white.slice((ymin..ymax, xmin..xmax)).count_true().eq(num + off_by) | self.is_white.slice((ymin..ymax, xmin..xmax)).count_true().eq(num - off_by)));
+            let claims = if let Some(off_by) = self.off_by {
+                vec![
+                    self.is_white.at((y, x)).imp(self.is_black.slice((ymin..ymax, xmin..xmax)).count_true().eq(num + off_by) | self.is_black.slice((ymin..ymax, xmin..xmax)).count_true().eq(num - off_by)),
+                    self.is_black.at((y, x)).imp(self.is_white.slice((ymin..ymax, xmin..xmax)).count_true().eq(num + off_by) | self.is_white.slice((ymin..ymax, xmin..xmax)).count_true().eq(num - off_by)),
+                ]
             } else {
-                self.solver.add_expr(self.is_white.at((y, x)).imp(self.is_black.slice((ymin..ymax, xmin..xmax)).count_true().eq(num)));
-                self.solver.add_expr(self.is_black.at((y, x)).imp(self.is_white.slice((ymin..ymax, xmin..xmax)).count_true().eq(num)));
-            }
+                vec![
+                    self.is_white.at((y, x)).imp(self.is_black.slice((ymin..ymax, xmin..xmax)).count_true().eq(num)),
+                    self.is_black.at((y, x)).imp(self.is_white.slice((ymin..ymax, xmin..xmax)).count_true().eq(num)),
+                ]
+            };
+            self.add_symbol_claims(claims);
         }
 
         Ok(())
     }
 
-    fn add_connect_all(&mut self, color: Color) -> Result<(), &'static str> {
+    fn add_connect_all(&mut self, rule: usize, color: Color) -> Result<(), SolverError> {
         match color {
             Color::White => graph::active_vertices_connected_2d(&mut self.solver, &self.is_white),
             Color::Black => graph::active_vertices_connected_2d(&mut self.solver, &self.is_black),
-            _ => return Err("connectAll with gray color"),
+            _ => return Err(SolverError::GrayColor { rule }),
         }
         Ok(())
     }
@@ -282,10 +474,10 @@ impl<'a> LogicPadSolver<'a> {
     }
 
     fn add_area_numbers(&mut self,
-        area_numbers: Option<&[AreaNumberTile]>,
+        area_numbers: Option<(usize, &[AreaNumberTile])>,
         size_light: Option<i32>,
         size_dark: Option<i32>,
-    ) -> Result<(), &'static str> {
+    ) -> Result<(), SolverError> {
         if area_numbers.is_none() && size_light.is_none() && size_dark.is_none() {
             return Ok(());
         }
@@ -299,10 +491,10 @@ impl<'a> LogicPadSolver<'a> {
 
         let mut cell_value = vec![vec![None; width]; height];
 
-        if let Some(area_numbers) = area_numbers {
+        if let Some((rule, area_numbers)) = area_numbers {
             for tile in area_numbers {
                 if cell_value[tile.y][tile.x].is_some() {
-                    return Err("duplicate area number");
+                    return Err(SolverError::DuplicateAreaNumber { rule, y: tile.y, x: tile.x });
                 }
 
                 cell_value[tile.y][tile.x] = Some(tile.number);
@@ -311,7 +503,8 @@ impl<'a> LogicPadSolver<'a> {
 
         for y in 0..height {
             for x in 0..width {
-                if size_light.is_some() || size_dark.is_some() {
+                // With a lyingSymbols rule, a number is tied to the size of its region to tell whether it holds.
+                if size_light.is_some() || size_dark.is_some() || (self.symbol_truths.is_some() && cell_value[y][x].is_some()) {
                     let sz = self.solver.int_var(1, (height * width) as i32);
 
                     if let Some(n) = size_light {
@@ -322,9 +515,9 @@ impl<'a> LogicPadSolver<'a> {
                     }
                     if let Some(n) = cell_value[y][x] {
                         if let Some(off_by) = self.off_by {
-                            self.solver.add_expr(sz.eq(n + off_by) | sz.eq(n - off_by));
+                            self.add_symbol_claims(vec![sz.eq(n + off_by) | sz.eq(n - off_by)]);
                         } else {
-                            self.solver.add_expr(sz.eq(n));
+                            self.add_symbol_claims(vec![sz.eq(n)]);
                         }
                     }
 
@@ -362,7 +555,25 @@ impl<'a> LogicPadSolver<'a> {
         Ok(())
     }
 
-    fn add_letters(&mut self, letters: &[LetterTile]) -> Result<(), &'static str> {
+    fn add_letters(&mut self, letters: &[LetterTile]) -> Result<(), SolverError> {
+        if self.symbol_truths.is_some() {
+            // The encoding below ties all letters together, so that each letter is given its own region instead.
+            for tile in letters {
+                let region = self.add_region(tile.y, tile.x);
+                let mut claims = vec![];
+                for other in letters {
+                    if other.letter == tile.letter {
+                        claims.push(region.at((other.y, other.x)).expr());
+                    } else {
+                        claims.push(!region.at((other.y, other.x)));
+                    }
+                }
+                self.add_symbol_claims(claims);
+            }
+
+            return Ok(());
+        }
+
         let mut letters_sorted = vec![];
         for tile in letters {
             letters_sorted.push((tile.letter.clone(), tile.y, tile.x));
@@ -453,7 +664,7 @@ impl<'a> LogicPadSolver<'a> {
         ret
     }
 
-    fn add_darts(&mut self, darts: &[DartTile]) -> Result<(), &'static str> {
+    fn add_darts(&mut self, darts: &[DartTile]) -> Result<(), SolverError> {
         for dart in darts {
             let y = dart.y;
             let x = dart.x;
@@ -463,23 +674,29 @@ impl<'a> LogicPadSolver<'a> {
             let bs = cells.iter().map(|&(y, x)| self.is_black.at((y, x))).collect::<Vec<_>>();
             let ws = cells.iter().map(|&(y, x)| self.is_white.at((y, x))).collect::<Vec<_>>();
 
-            if let Some(off_by) = self.off_by {
-                self.solver.add_expr(self.is_black.at((y, x)).imp(count_true(&ws).eq(dart.number + off_by) | count_true(&ws).eq(dart.number - off_by)));
-                self.solver.add_expr(self.is_white.at((y, x)).imp(count_true(&bs).eq(dart.number + off_by) | count_true(&bs).eq(dart.number - off_by)));
+            let claims = if let Some(off_by) = self.off_by {
+                vec![
+                    self.is_black.at((y, x)).imp(count_true(&ws).eq(dart.number + off_by) | count_true(&ws).eq(dart.number - off_by)),
+                    self.is_white.at((y, x)).imp(count_true(&bs).eq(dart.number + off_by) | count_true(&bs).eq(dart.number - off_by)),
+                ]
             } else {
-                self.solver.add_expr(self.is_black.at((y, x)).imp(count_true(&ws).eq(dart.number)));
-                self.solver.add_expr(self.is_white.at((y, x)).imp(count_true(&bs).eq(dart.number)));
-            }
+                vec![
+                    self.is_black.at((y, x)).imp(count_true(&ws).eq(dart.number)),
+                    self.is_white.at((y, x)).imp(count_true(&bs).eq(dart.number)),
+                ]
+            };
+            self.add_symbol_claims(claims);
         }
 
         Ok(())
     }
 
-    fn add_viewpoints(&mut self, viewpoints: &[ViewpointTile]) -> Result<(), &'static str> {
+    fn add_viewpoints(&mut self, viewpoints: &[ViewpointTile]) -> Result<(), SolverError> {
         for tile in viewpoints {
             let y = tile.y;
             let x = tile.x;
             let num = tile.number;
+            let mut claims = vec![];
 
             for a in [&self.is_black, &self.is_white] {
                 let mut e = int_constant(1);
@@ -496,53 +713,28 @@ impl<'a> LogicPadSolver<'a> {
                 }
 
                 if let Some(off_by) = self.off_by {
-                    self.solver.add_expr(a.at((y, x)).imp(e.eq(num + off_by) | e.eq(num - off_by)));
+                    claims.push(a.at((y, x)).imp(e.eq(num + off_by) | e.eq(num - off_by)));
                 } else {
-                    self.solver.add_expr(a.at((y, x)).imp(e.eq(num)));
+                    claims.push(a.at((y, x)).imp(e.eq(num)));
                 }
             }
+
+            self.add_symbol_claims(claims);
         }
 
         Ok(())
     }
 
-    fn add_lotus_or_galaxy(&mut self, y: usize, x: usize, sy: usize, sx: usize, ori: Option<Orientation>) -> Result<(), &'static str> {
+    fn add_lotus_or_galaxy(&mut self, rule: usize, y: usize, x: usize, sy: usize, sx: usize, ori: Option<Orientation>) -> Result<(), SolverError> {
         let height = self.height;
         let width = self.width;
 
         if !(y < height && x < width) {
-            return Err("lotus out of bounds");
+            return Err(SolverError::SymbolOutOfBounds { rule, y: sy, x: sx });
         }
 
-        let block_cells = &self.solver.bool_var_2d((height, width));
-        graph::active_vertices_connected_2d(&mut self.solver, block_cells);
-        self.solver.add_expr(block_cells.at((y, x)));
-
-        let mut adj_pairs = vec![];
-        for y in 0..height {
-            for x in 0..width {
-                if y > 0 {
-                    adj_pairs.push(((y, x), (y - 1, x)));
-                }
-                if x > 0 {
-                    adj_pairs.push(((y, x), (y, x - 1)));
-                }
-            }
-        }
-        for (p, q) in adj_pairs {
-            self.solver.add_expr(
-                (self.is_black.at(p) & self.is_black.at(q)).imp(block_cells.at(p).iff(block_cells.at(q)))
-            );
-            self.solver.add_expr(
-                (self.is_white.at(p) & self.is_white.at(q)).imp(block_cells.at(p).iff(block_cells.at(q)))
-            );
-            self.solver.add_expr(
-                (self.is_black.at(p) ^ self.is_black.at(q)).imp(!block_cells.at(p) | !block_cells.at(q))
-            );
-            self.solver.add_expr(
-                (self.is_white.at(p) ^ self.is_white.at(q)).imp(!block_cells.at(p) | !block_cells.at(q))
-            );
-        }
+        let block_cells = &self.add_region(y, x);
+        let mut claims = vec![];
 
         for y in 0..(height as i32) {
             for x in 0..(width as i32) {
@@ -565,48 +757,50 @@ impl<'a> LogicPadSolver<'a> {
                 };
 
                 if !(0 <= y2 && y2 < height as i32 && 0 <= x2 && x2 < width as i32) {
-                    self.solver.add_expr(!block_cells.at((y as usize, x as usize)));
+                    claims.push(!block_cells.at((y as usize, x as usize)));
                     continue;
                 }
 
                 if (y, x) < (y2, x2) {
-                    self.solver.add_expr(block_cells.at((y as usize, x as usize)).iff(block_cells.at((y2 as usize, x2 as usize))));
+                    claims.push(block_cells.at((y as usize, x as usize)).iff(block_cells.at((y2 as usize, x2 as usize))));
                 }
             }
         }
 
+        self.add_symbol_claims(claims);
+
         Ok(())
     }
 
-    fn add_lotuses(&mut self, lotuses: &[LotusTile]) -> Result<(), &'static str> {
+    fn add_lotuses(&mut self, rule: usize, lotuses: &[LotusTile]) -> Result<(), SolverError> {
         for tile in lotuses {
             match tile.orientation {
                 Orientation::Down | Orientation::Up => {
                     if tile.y % 2 == 0 {
-                        self.add_lotus_or_galaxy(tile.y / 2, tile.x / 2, tile.y, tile.x, Some(tile.orientation))?;
+                        self.add_lotus_or_galaxy(rule, tile.y / 2, tile.x / 2, tile.y, tile.x, Some(tile.orientation))?;
                     } else {
-                        return Err("lotus on invalid position");
+                        return Err(SolverError::InvalidLotusPosition { rule, y: tile.y, x: tile.x });
                     }
                 }
                 Orientation::Left | Orientation::Right => {
                     if tile.x % 2 == 0 {
-                        self.add_lotus_or_galaxy(tile.y / 2, tile.x / 2, tile.y, tile.x, Some(tile.orientation))?;
+                        self.add_lotus_or_galaxy(rule, tile.y / 2, tile.x / 2, tile.y, tile.x, Some(tile.orientation))?;
                     } else {
-                        return Err("lotus on invalid position");
+                        return Err(SolverError::InvalidLotusPosition { rule, y: tile.y, x: tile.x });
                     }
                 }
                 Orientation::DownLeft | Orientation::UpRight => {
                     if tile.x % 2 == 0 && tile.y % 2 == 0 {
-                        self.add_lotus_or_galaxy(tile.y / 2, tile.x / 2, tile.y, tile.x, Some(tile.orientation))?;
+                        self.add_lotus_or_galaxy(rule, tile.y / 2, tile.x / 2, tile.y, tile.x, Some(tile.orientation))?;
                     } else {
-                        return Err("lotus on invalid position");
+                        return Err(SolverError::InvalidLotusPosition { rule, y: tile.y, x: tile.x });
                     }
                 }
                 Orientation::DownRight | Orientation::UpLeft => {
                     if tile.x % 2 == 0 && tile.y % 2 == 0 {
-                        self.add_lotus_or_galaxy(tile.y / 2, tile.x / 2, tile.y, tile.x, Some(tile.orientation))?;
+                        self.add_lotus_or_galaxy(rule, tile.y / 2, tile.x / 2, tile.y, tile.x, Some(tile.orientation))?;
                     } else {
-                        return Err("lotus on invalid position");
+                        return Err(SolverError::InvalidLotusPosition { rule, y: tile.y, x: tile.x });
                     }
                 }
             }
@@ -615,48 +809,56 @@ impl<'a> LogicPadSolver<'a> {
         Ok(())
     }
 
-    fn add_galaxies(&mut self, galaxies: &[GalaxyTile]) -> Result<(), &'static str> {
+    fn add_galaxies(&mut self, rule: usize, galaxies: &[GalaxyTile]) -> Result<(), SolverError> {
         for tile in galaxies {
             if tile.x % 2 == 1 && tile.y % 2 == 1 {
-                return Err("galaxies on corners may cause unexpected behavior");
+                return Err(SolverError::GalaxyOnCorner { rule, y: tile.y, x: tile.x });
             }
-            self.add_lotus_or_galaxy(tile.y / 2, tile.x / 2, tile.y, tile.x, None)?;
+            self.add_lotus_or_galaxy(rule, tile.y / 2, tile.x / 2, tile.y, tile.x, None)?;
         }
 
         Ok(())
     }
 
-    fn add_same_shape(&mut self, color: Color) {
+    fn add_same_shape(&mut self, rule: usize, color: Color) -> Result<(), SolverError> {
         self.solver.add_custom_constraint(
             Box::new(ShapesConstraint::new(self.height, self.width, ConstraintType::AllEqual)),
             match color {
                 Color::White => &self.is_white,
                 Color::Black => &self.is_black,
-                _ => panic!(),
+                _ => return Err(SolverError::GrayColor { rule }),
             },
         );
+        Ok(())
     }
 
-    fn add_unique_shape(&mut self, color: Color) {
+    fn add_unique_shape(&mut self, rule: usize, color: Color) -> Result<(), SolverError> {
         self.solver.add_custom_constraint(
             Box::new(ShapesConstraint::new(self.height, self.width, ConstraintType::AllDifferent)),
             match color {
                 Color::White => &self.is_white,
                 Color::Black => &self.is_black,
-                _ => panic!(),
+                _ => return Err(SolverError::GrayColor { rule }),
             },
         );
+        Ok(())
     }
 
-    fn add_cell_count(&mut self, color: Color, count: i32) {
+    fn add_cell_count(&mut self, rule: usize, color: Color, count: i32) -> Result<(), SolverError> {
         match color {
             Color::White => self.solver.add_expr(self.is_white.count_true().eq(count)),
             Color::Black => self.solver.add_expr(self.is_black.count_true().eq(count)),
-            _ => panic!(),
+            _ => return Err(SolverError::GrayColor { rule }),
         }
+        Ok(())
+    }
+
+    fn add_lying_symbols(&mut self, number: i32) {
+        let truths = self.symbol_truths.as_ref().unwrap();
+        self.solver.add_expr(count_true(truths).eq(truths.len() as i32 - number));
     }
 
-    fn add_symbol_count(&mut self, constraints: &[(i32, SymbolCountKind, Color)], symbol_cells: &[(usize, usize)]) {
+    fn add_symbol_count(&mut self, constraints: &[(usize, i32, SymbolCountKind, Color)], symbol_cells: &[(usize, usize)]) -> Result<(), SolverError> {
         let height = self.height;
         let width = self.width;
 
@@ -697,11 +899,11 @@ impl<'a> LogicPadSolver<'a> {
             symbol_group.push(indicator);
         }
 
-        for &(number, kind, color) in constraints {
+        for &(rule, number, kind, color) in constraints {
             let target = match color {
                 Color::White => &self.is_white,
                 Color::Black => &self.is_black,
-                _ => panic!(),
+                _ => return Err(SolverError::GrayColor { rule }),
             };
             for y in 0..height {
                 for x in 0..width {
@@ -724,9 +926,70 @@ impl<'a> LogicPadSolver<'a> {
                 }
             }
         }
+
+        Ok(())
+    }
+
+    fn board_from_model(&self, is_white: &[Vec<bool>], is_black: &[Vec<bool>]) -> Board {
+        let mut result = vec![vec![None; self.width]; self.height];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                match (is_white[y][x], is_black[y][x]) {
+                    (true, false) => {
+                        result[y][x] = Some(Color::White);
+                    }
+                    (false, true) => {
+                        result[y][x] = Some(Color::Black);
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        result
+    }
+
+    fn forbid_color(&mut self, y: usize, x: usize, color: Color) {
+        match color {
+            Color::White => self.solver.add_expr(!self.is_white.at((y, x))),
+            Color::Black => self.solver.add_expr(!self.is_black.at((y, x))),
+            Color::Undecided => (),
+        }
+    }
+
+    fn find_solution(&mut self) -> Option<Board> {
+        let (is_white, is_black) = {
+            let model = self.solver.solve()?;
+            (model.get(&self.is_white), model.get(&self.is_black))
+        };
+        Some(self.board_from_model(&is_white, &is_black))
+    }
+
+    // Finds a solution and excludes it from further calls, so that repeated calls
+    // enumerate pairwise distinct solutions.
+    fn next_solution(&mut self) -> Option<Board> {
+        let (is_white, is_black) = {
+            let model = self.solver.solve()?;
+            (model.get(&self.is_white), model.get(&self.is_black))
+        };
+
+        // is_black is determined by is_white on existing tiles, so it suffices to block is_white
+        let mut differences = vec![];
+        for (y, row) in is_white.iter().enumerate() {
+            for (x, &white) in row.iter().enumerate() {
+                if white {
+                    differences.push(!self.is_white.at((y, x)));
+                } else {
+                    differences.push(self.is_white.at((y, x)).expr());
+                }
+            }
+        }
+        self.solver.add_expr(any(differences));
+
+        Some(self.board_from_model(&is_white, &is_black))
     }
 
-    fn solve(self, underclued: bool) -> Option<Vec<Vec<Option<Color>>>> {
+    fn solve(mut self, underclued: bool) -> Option<Board> {
         if underclued {
             let model = self.solver.irrefutable_facts()?;
 
@@ -749,52 +1012,76 @@ impl<'a> LogicPadSolver<'a> {
 
             Some(result)
         } else {
-            let mut solver = self.solver;
-            let model = solver.solve()?;
+            self.find_solution()
+        }
+    }
 
-            let is_white = model.get(&self.is_white);
-            let is_black = model.get(&self.is_black);
-            let mut result = vec![vec![None; self.width]; self.height];
-            for y in 0..self.height {
-                for x in 0..self.width {
-                    match (is_white[y][x], is_black[y][x]) {
-                        (true, false) => {
-                            result[y][x] = Some(Color::White);
-                        }
-                        (false, true) => {
-                            result[y][x] = Some(Color::Black);
-                        }
-                        _ => (),
-                    }
-                }
+    fn enumerate(mut self, limit: usize) -> Vec<Board> {
+        let mut solutions = vec![];
+        while solutions.len() < limit {
+            match self.next_solution() {
+                Some(solution) => solutions.push(solution),
+                None => break,
             }
+        }
+        solutions
+    }
 
-            Some(result)
+    fn check_uniqueness(mut self) -> Uniqueness {
+        let first = match self.next_solution() {
+            Some(solution) => solution,
+            None => return Uniqueness::None,
+        };
+
+        match self.next_solution() {
+            Some(second) => Uniqueness::Multiple { solutions: [first, second] },
+            None => Uniqueness::Unique { solution: first },
+        }
+    }
+
+    fn count(mut self, cap: usize) -> SolutionCount {
+        let mut count = 0;
+        while count < cap {
+            if self.next_solution().is_none() {
+                return SolutionCount { count, exact: true };
+            }
+            count += 1;
+        }
+
+        SolutionCount {
+            count,
+            exact: self.next_solution().is_none(),
         }
     }
 }
 
-pub fn solve(puzzle: &Puzzle, underclued: bool) -> Result<Option<Vec<Vec<Option<Color>>>>, &'static str> {
+fn build_solver<'a>(puzzle: &Puzzle) -> Result<LogicPadSolver<'a>, SolverError> {
+    validate::check(puzzle)?;
+
     let mut off_by = None;
+    let mut lying_symbols = None;
 
-    for rule in &puzzle.rules {
+    for (i, rule) in puzzle.rules.iter().enumerate() {
         match rule {
             Rule::OffByX { number } => {
                 if off_by.is_some() {
-                    return Err("multiple offByX rules");
+                    return Err(SolverError::MultipleOffByX { rule: i });
                 }
                 if *number < 0 {
-                    return Err("offByX with negative number");
+                    return Err(SolverError::NegativeOffByX { rule: i });
                 }
                 if *number > 0 {
                     off_by = Some(*number);
                 }
             }
+            Rule::LyingSymbols { number } => {
+                lying_symbols = Some(*number);
+            }
             _ => (),
         }
     }
 
-    let mut solver = LogicPadSolver::new(puzzle.height, puzzle.width, off_by);
+    let mut solver = LogicPadSolver::new(puzzle.height, puzzle.width, off_by, lying_symbols.is_some());
 
     solver.add_tiles(&puzzle.tiles)?;
     solver.add_connections(&puzzle.connections);
@@ -804,23 +1091,23 @@ pub fn solve(puzzle: &Puzzle, underclued: bool) -> Result<Option<Vec<Vec<Option<
 
     let mut symbol_count_constraints = vec![];
 
-    for rule in &puzzle.rules {
+    for (i, rule) in puzzle.rules.iter().enumerate() {
         match rule {
             Rule::ConnectAll { color } => {
                 match *color {
                     Color::White => has_connect_all_white = true,
                     Color::Black => has_connect_all_black = true,
-                    _ => return Err("connectAll with gray color"),
+                    _ => return Err(SolverError::GrayColor { rule: i }),
                 }
-                solver.add_connect_all(*color)?;
+                solver.add_connect_all(i, *color)?;
             }
             Rule::ForbiddenPattern { pattern } => {
-                solver.add_forbidden_pattern(pattern)?;
+                solver.add_forbidden_pattern(i, pattern)?;
             }
             Rule::Minesweeper { tiles } => {
                 for tile in tiles {
                     if !puzzle.tiles[tile.y][tile.x].exists {
-                        return Err("minesweeper tile on non-existing tile; don't do this");
+                        return Err(SolverError::SymbolOnNonExistingTile { rule: i, y: tile.y, x: tile.x });
                     }
                 }
                 solver.add_minesweeper(tiles)?;
@@ -829,7 +1116,7 @@ pub fn solve(puzzle: &Puzzle, underclued: bool) -> Result<Option<Vec<Vec<Option<
             Rule::Letter { tiles } => {
                 for tile in tiles {
                     if !puzzle.tiles[tile.y][tile.x].exists {
-                        return Err("letter tile on non-existing tile; don't do this");
+                        return Err(SolverError::SymbolOnNonExistingTile { rule: i, y: tile.y, x: tile.x });
                     }
                 }
                 solver.add_letters(tiles)?;
@@ -837,7 +1124,7 @@ pub fn solve(puzzle: &Puzzle, underclued: bool) -> Result<Option<Vec<Vec<Option<
             Rule::Dart { tiles } => {
                 for tile in tiles {
                     if !puzzle.tiles[tile.y][tile.x].exists {
-                        return Err("dart tile on non-existing tile; don't do this");
+                        return Err(SolverError::SymbolOnNonExistingTile { rule: i, y: tile.y, x: tile.x });
                     }
                 }
                 solver.add_darts(tiles)?;
@@ -845,31 +1132,32 @@ pub fn solve(puzzle: &Puzzle, underclued: bool) -> Result<Option<Vec<Vec<Option<
             Rule::Viewpoint { tiles } => {
                 for tile in tiles {
                     if !puzzle.tiles[tile.y][tile.x].exists {
-                        return Err("viewpoint tile on non-existing tile; don't do this");
+                        return Err(SolverError::SymbolOnNonExistingTile { rule: i, y: tile.y, x: tile.x });
                     }
                 }
                 solver.add_viewpoints(tiles)?;
             }
             Rule::Lotus { tiles } => {
-                solver.add_lotuses(tiles)?;
+                solver.add_lotuses(i, tiles)?;
             }
             Rule::Galaxy { tiles } => {
-                solver.add_galaxies(tiles)?;
+                solver.add_galaxies(i, tiles)?;
             }
             Rule::SameShape { color } => {
-                solver.add_same_shape(*color);
+                solver.add_same_shape(i, *color)?;
             }
             Rule::UniqueShape { color } => {
-                solver.add_unique_shape(*color);
+                solver.add_unique_shape(i, *color)?;
             }
             Rule::RegionArea { color: _, size: _ } => (),
             Rule::CellCount { color, count } => {
-                solver.add_cell_count(*color, *count);
+                solver.add_cell_count(i, *color, *count)?;
             }
             Rule::OffByX { number: _ } => (),
             &Rule::SymbolCount { number, kind, color } => {
-                symbol_count_constraints.push((number, kind, color));
+                symbol_count_constraints.push((i, number, kind, color));
             }
+            Rule::LyingSymbols { number: _ } => (),
         }
     }
 
@@ -879,7 +1167,7 @@ pub fn solve(puzzle: &Puzzle, underclued: bool) -> Result<Option<Vec<Vec<Option<
 
     if !symbol_count_constraints.is_empty() {
         let mut symbol_cells = vec![];
-        for rule in &puzzle.rules {
+        for (i, rule) in puzzle.rules.iter().enumerate() {
             match rule {
                 Rule::ConnectAll { color: _ } => (),
                 Rule::ForbiddenPattern { pattern: _ } => (),
@@ -911,7 +1199,7 @@ pub fn solve(puzzle: &Puzzle, underclued: bool) -> Result<Option<Vec<Vec<Option<
                 Rule::Lotus { tiles } => {
                     for tile in tiles {
                         if !(tile.y % 2 == 0 && tile.x % 2 == 0) {
-                            return Err("lotus not on cell center is not supported for symbol count");
+                            return Err(SolverError::SymbolNotOnCellCenter { rule: i, y: tile.y, x: tile.x });
                         }
                         symbol_cells.push((tile.y / 2, tile.x / 2));
                     }
@@ -919,7 +1207,7 @@ pub fn solve(puzzle: &Puzzle, underclued: bool) -> Result<Option<Vec<Vec<Option<
                 Rule::Galaxy { tiles } => {
                     for tile in tiles {
                         if !(tile.y % 2 == 0 && tile.x % 2 == 0) {
-                            return Err("galaxy not on cell center is not supported for symbol count");
+                            return Err(SolverError::SymbolNotOnCellCenter { rule: i, y: tile.y, x: tile.x });
                         }
                         symbol_cells.push((tile.y / 2, tile.x / 2));
                     }
@@ -930,40 +1218,41 @@ pub fn solve(puzzle: &Puzzle, underclued: bool) -> Result<Option<Vec<Vec<Option<
                 Rule::CellCount { color: _, count: _ } => (),
                 Rule::OffByX { number: _ } => (),
                 Rule::SymbolCount { number: _, kind: _, color: _ } => (),
+                Rule::LyingSymbols { number: _ } => (),
             }
         }
 
-        solver.add_symbol_count(&symbol_count_constraints, &symbol_cells);
+        solver.add_symbol_count(&symbol_count_constraints, &symbol_cells)?;
     }
 
     // Area size constraints
-    let mut area_number: Option<&[AreaNumberTile]> = None;
+    let mut area_number: Option<(usize, &[AreaNumberTile])> = None;
     let mut size_light = None;
     let mut size_dark = None;
 
-    for rule in &puzzle.rules {
+    for (i, rule) in puzzle.rules.iter().enumerate() {
         match rule {
             Rule::AreaNumber { tiles } => {
                 if area_number.is_some() {
-                    return Err("multiple area number rules");
+                    return Err(SolverError::MultipleAreaNumberRules { rule: i });
                 }
-                area_number = Some(tiles);
+                area_number = Some((i, tiles));
             }
             Rule::RegionArea { color, size } => {
                 match *color {
                     Color::White => {
                         if size_light.is_some() {
-                            return Err("multiple light area size rules");
+                            return Err(SolverError::MultipleRegionAreaRules { rule: i });
                         }
                         size_light = Some(*size);
                     }
                     Color::Black => {
                         if size_dark.is_some() {
-                            return Err("multiple dark area size rules");
+                            return Err(SolverError::MultipleRegionAreaRules { rule: i });
                         }
                         size_dark = Some(*size);
                     }
-                    _ => panic!(),
+                    _ => return Err(SolverError::GrayColor { rule: i }),
                 }
             }
             _ => (),
@@ -972,5 +1261,108 @@ pub fn solve(puzzle: &Puzzle, underclued: bool) -> Result<Option<Vec<Vec<Option<
 
     solver.add_area_numbers(area_number, size_light, size_dark)?;
 
+    // Every symbol has been added by now.
+    if let Some(number) = lying_symbols {
+        solver.add_lying_symbols(number);
+    }
+
+    Ok(solver)
+}
+
+/// Solves `puzzle` and returns `None` if it has no solution.
+///
+/// If `underclued` is false, an arbitrary solution is returned. Otherwise, only the cells whose colors
+/// are common to all solutions are filled.
+pub fn solve(puzzle: &Puzzle, underclued: bool) -> Result<Option<Board>, SolverError> {
+    let solver = build_solver(puzzle)?;
     Ok(solver.solve(underclued))
 }
+
+/// Returns up to `limit` pairwise distinct solutions of `puzzle`.
+pub fn enumerate_solutions(puzzle: &Puzzle, limit: usize) -> Result<Vec<Board>, SolverError> {
+    let solver = build_solver(puzzle)?;
+    Ok(solver.enumerate(limit))
+}
+
+/// Determines whether `puzzle` has no solution, exactly one solution or multiple solutions.
+pub fn check_uniqueness(puzzle: &Puzzle) -> Result<Uniqueness, SolverError> {
+    let solver = build_solver(puzzle)?;
+    Ok(solver.check_uniqueness())
+}
+
+/// Counts the solutions of `puzzle`, stopping at `cap`.
+pub fn count_solutions(puzzle: &Puzzle, cap: usize) -> Result<SolutionCount, SolverError> {
+    let solver = build_solver(puzzle)?;
+    Ok(solver.count(cap))
+}
+
+/// Computes the cells whose colors are common to all solutions of `puzzle` like [`solve`] with `underclued`,
+/// but gives up with the facts proven so far once `budget` is exhausted.
+///
+/// Each remaining cell is proven by a separate solver call, which is slower than [`solve`] when the budget suffices.
+/// Without any limit, this is the same as [`solve`].
+pub fn solve_with_budget(puzzle: &Puzzle, budget: &Budget) -> Result<BudgetedSolution, SolverError> {
+    if budget.time_limit.is_none() && budget.max_solver_calls.is_none() {
+        return Ok(match solve(puzzle, true)? {
+            Some(facts) => BudgetedSolution::Solved { facts },
+            None => BudgetedSolution::None,
+        });
+    }
+
+    solve_with_progress(puzzle, budget, |_, _, _| ())
+}
+
+/// Same as [`solve_with_budget`], but calls `on_fact(y, x, color)` as soon as each cell is proven to have `color`
+/// in all solutions, even without any limit in `budget`.
+pub fn solve_with_progress(
+    puzzle: &Puzzle,
+    budget: &Budget,
+    mut on_fact: impl FnMut(usize, usize, Color),
+) -> Result<BudgetedSolution, SolverError> {
+    let mut tracker = BudgetTracker::new(budget)?;
+    let mut facts = vec![vec![None; puzzle.width]; puzzle.height];
+
+    let mut solver = build_solver(puzzle)?;
+    if !tracker.spend() {
+        return Ok(BudgetedSolution::Timeout { facts });
+    }
+    // Cells which agree in all solutions found so far
+    let mut candidates = match solver.find_solution() {
+        Some(solution) => solution,
+        None => return Ok(BudgetedSolution::None),
+    };
+
+    // A candidate is proven once forbidding its color leaves no solution; otherwise, the other solution rules out
+    // every candidate it disagrees with.
+    for y in 0..puzzle.height {
+        for x in 0..puzzle.width {
+            let color = match candidates[y][x] {
+                Some(color) => color,
+                None => continue,
+            };
+            if !tracker.spend() {
+                return Ok(BudgetedSolution::Timeout { facts });
+            }
+
+            let mut solver = build_solver(puzzle)?;
+            solver.forbid_color(y, x, color);
+            match solver.find_solution() {
+                Some(other) => {
+                    for (candidate_row, other_row) in candidates.iter_mut().zip(other.iter()) {
+                        for (candidate, other) in candidate_row.iter_mut().zip(other_row.iter()) {
+                            if candidate != other {
+                                *candidate = None;
+                            }
+                        }
+                    }
+                }
+                None => {
+                    facts[y][x] = Some(color);
+                    on_fact(y, x, color);
+                }
+            }
+        }
+    }
+
+    Ok(BudgetedSolution::Solved { facts })
+}
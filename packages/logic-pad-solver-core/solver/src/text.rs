@@ -0,0 +1,529 @@
+//! A plain-text notation for puzzles and boards, convenient for fixtures and terminal output.
+//!
+//! A puzzle consists of an optional header `WIDTHxHEIGHT` and the grid, optionally followed by a line `--` and one
+//! rule, symbol or connection per line:
+//!
+//! ```text
+//! 5x2
+//! O.#..
+//! .. ..
+//! --
+//! connectAll dark
+//! forbiddenPattern OO/O.
+//! minesweeper 1 0 3
+//! lotus 2 4 up
+//! connect 0 0 0 1
+//! ```
+//!
+//! In the grid, `O` is a fixed light tile, `#` is a fixed dark tile, `.` is any other existing tile and a space is
+//! a non-existing tile. Every line before `--` is a row, and rows shorter than the width are padded with non-existing
+//! tiles. Without the header, the width is the length of the longest row. Colors of tiles which are not fixed are not
+//! represented.
+//!
+//! Coordinates are written as `y x` (doubled for `lotus` and `galaxy`, as in the JSON format). The available lines are:
+//!
+//! - `connectAll COLOR`, `sameShape COLOR`, `uniqueShape COLOR`
+//! - `forbiddenPattern ROW/ROW/...` where each row consists of `O`, `#` and `.`
+//! - `regionArea COLOR SIZE`, `cellCount COLOR COUNT`, `offByX NUMBER`
+//! - `symbolCount (exactly|atLeast|atMost) NUMBER COLOR`, `lyingSymbols NUMBER`
+//! - `minesweeper Y X NUMBER`, `number Y X NUMBER`, `letter Y X LETTER`, `dart Y X ORIENTATION NUMBER`,
+//!   `viewpoint Y X NUMBER`, `lotus Y X ORIENTATION`, `galaxy Y X`
+//! - `connect Y1 X1 Y2 X2`
+//!
+//! where `COLOR` is `light` or `dark` and `ORIENTATION` is one of the orientations of the JSON format
+//! (e.g. `up-right`).
+//! All symbols of the same kind are gathered into one rule, placed where the first of them appears.
+
+use std::fmt;
+
+use crate::error::SolverError;
+use crate::puzzle::{
+    add_symbol, AreaNumberTile, Color, Connection, DartTile, GalaxyTile, LetterTile, LotusTile, MinesweeperTile,
+    Orientation, Puzzle, Rule, SymbolCountKind, Tile, ViewpointTile, CURRENT_VERSION,
+};
+use crate::solver::Board;
+
+/// An error in the text notation. `line` is 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+const SEPARATOR: &str = "--";
+
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::White => "light",
+        Color::Black => "dark",
+        Color::Undecided => "gray",
+    }
+}
+
+fn parse_color(s: &str) -> Result<Color, String> {
+    match s {
+        "light" => Ok(Color::White),
+        "dark" => Ok(Color::Black),
+        "gray" => Ok(Color::Undecided),
+        _ => Err(format!("unknown color: {}", s)),
+    }
+}
+
+const ORIENTATIONS: [(Orientation, &str); 8] = [
+    (Orientation::Left, "left"),
+    (Orientation::Right, "right"),
+    (Orientation::Up, "up"),
+    (Orientation::Down, "down"),
+    (Orientation::UpRight, "up-right"),
+    (Orientation::UpLeft, "up-left"),
+    (Orientation::DownRight, "down-right"),
+    (Orientation::DownLeft, "down-left"),
+];
+
+fn orientation_name(orientation: Orientation) -> &'static str {
+    ORIENTATIONS.iter().find(|&&(o, _)| o == orientation).unwrap().1
+}
+
+fn parse_orientation(s: &str) -> Result<Orientation, String> {
+    match ORIENTATIONS.iter().find(|&&(_, name)| name == s) {
+        Some(&(o, _)) => Ok(o),
+        None => Err(format!("unknown orientation: {}", s)),
+    }
+}
+
+fn symbol_count_kind_name(kind: SymbolCountKind) -> &'static str {
+    match kind {
+        SymbolCountKind::AtMost => "atMost",
+        SymbolCountKind::AtLeast => "atLeast",
+        SymbolCountKind::Exactly => "exactly",
+    }
+}
+
+fn parse_symbol_count_kind(s: &str) -> Result<SymbolCountKind, String> {
+    match s {
+        "atMost" => Ok(SymbolCountKind::AtMost),
+        "atLeast" => Ok(SymbolCountKind::AtLeast),
+        "exactly" => Ok(SymbolCountKind::Exactly),
+        _ => Err(format!("unknown symbol count kind: {}", s)),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(s: &str) -> Result<T, String> {
+    s.parse().map_err(|_| format!("invalid number: {}", s))
+}
+
+fn tile_char(tile: &Tile) -> char {
+    if !tile.exists {
+        ' '
+    } else if tile.fixed && tile.color == Color::White {
+        'O'
+    } else if tile.fixed && tile.color == Color::Black {
+        '#'
+    } else {
+        '.'
+    }
+}
+
+fn parse_tile(c: char) -> Result<Tile, String> {
+    match c {
+        'O' => Ok(Tile { exists: true, fixed: true, color: Color::White }),
+        '#' => Ok(Tile { exists: true, fixed: true, color: Color::Black }),
+        '.' => Ok(Tile { exists: true, fixed: false, color: Color::Undecided }),
+        ' ' => Ok(Tile { exists: false, fixed: false, color: Color::Undecided }),
+        _ => Err(format!("unknown tile: {:?}", c)),
+    }
+}
+
+fn format_pattern(pattern: &[Vec<Tile>]) -> String {
+    let rows = pattern
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|tile| match tile.color {
+                    Color::White => 'O',
+                    Color::Black => '#',
+                    Color::Undecided => '.',
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>();
+    rows.join("/")
+}
+
+fn parse_pattern(s: &str) -> Result<Vec<Vec<Tile>>, String> {
+    let mut pattern = vec![];
+    for row in s.split('/') {
+        let mut tiles = vec![];
+        for c in row.chars() {
+            let color = match c {
+                'O' => Color::White,
+                '#' => Color::Black,
+                '.' => Color::Undecided,
+                _ => return Err(format!("unknown pattern tile: {:?}", c)),
+            };
+            tiles.push(Tile { exists: true, fixed: color != Color::Undecided, color });
+        }
+        pattern.push(tiles);
+    }
+    Ok(pattern)
+}
+
+/// Formats `puzzle` in the text notation.
+pub fn format_puzzle(puzzle: &Puzzle) -> String {
+    let mut ret = format!("{}x{}\n", puzzle.width, puzzle.height);
+
+    for row in &puzzle.tiles {
+        ret.extend(row.iter().map(tile_char));
+        ret.push('\n');
+    }
+
+    let mut lines = vec![];
+    for rule in &puzzle.rules {
+        match rule {
+            Rule::ConnectAll { color } => lines.push(format!("connectAll {}", color_name(*color))),
+            Rule::ForbiddenPattern { pattern } => lines.push(format!("forbiddenPattern {}", format_pattern(pattern))),
+            Rule::Minesweeper { tiles } => {
+                for t in tiles {
+                    lines.push(format!("minesweeper {} {} {}", t.y, t.x, t.number));
+                }
+            }
+            Rule::AreaNumber { tiles } => {
+                for t in tiles {
+                    lines.push(format!("number {} {} {}", t.y, t.x, t.number));
+                }
+            }
+            Rule::Letter { tiles } => {
+                for t in tiles {
+                    lines.push(format!("letter {} {} {}", t.y, t.x, t.letter));
+                }
+            }
+            Rule::Dart { tiles } => {
+                for t in tiles {
+                    lines.push(format!("dart {} {} {} {}", t.y, t.x, orientation_name(t.orientation), t.number));
+                }
+            }
+            Rule::Viewpoint { tiles } => {
+                for t in tiles {
+                    lines.push(format!("viewpoint {} {} {}", t.y, t.x, t.number));
+                }
+            }
+            Rule::Lotus { tiles } => {
+                for t in tiles {
+                    lines.push(format!("lotus {} {} {}", t.y, t.x, orientation_name(t.orientation)));
+                }
+            }
+            Rule::Galaxy { tiles } => {
+                for t in tiles {
+                    lines.push(format!("galaxy {} {}", t.y, t.x));
+                }
+            }
+            Rule::SameShape { color } => lines.push(format!("sameShape {}", color_name(*color))),
+            Rule::UniqueShape { color } => lines.push(format!("uniqueShape {}", color_name(*color))),
+            Rule::RegionArea { color, size } => lines.push(format!("regionArea {} {}", color_name(*color), size)),
+            Rule::CellCount { color, count } => lines.push(format!("cellCount {} {}", color_name(*color), count)),
+            Rule::OffByX { number } => lines.push(format!("offByX {}", number)),
+            Rule::SymbolCount { number, kind, color } => lines.push(format!(
+                "symbolCount {} {} {}",
+                symbol_count_kind_name(*kind),
+                number,
+                color_name(*color)
+            )),
+            Rule::LyingSymbols { number } => lines.push(format!("lyingSymbols {}", number)),
+        }
+    }
+    for conn in &puzzle.connections {
+        lines.push(format!("connect {} {} {} {}", conn.y1, conn.x1, conn.y2, conn.x2));
+    }
+
+    if !lines.is_empty() {
+        ret.push_str(SEPARATOR);
+        ret.push('\n');
+        for line in lines {
+            ret.push_str(&line);
+            ret.push('\n');
+        }
+    }
+
+    ret
+}
+
+fn expect_args(args: &[&str], n: usize) -> Result<(), String> {
+    if args.len() != n {
+        return Err(format!("expected {} arguments, found {}", n, args.len()));
+    }
+    Ok(())
+}
+
+fn parse_line(line: &str, rules: &mut Vec<Rule>, connections: &mut Vec<Connection>) -> Result<(), String> {
+    let mut tokens = line.split_whitespace();
+    let kind = match tokens.next() {
+        Some(kind) => kind,
+        None => return Ok(()),
+    };
+    let args = tokens.collect::<Vec<_>>();
+
+    match kind {
+        "connectAll" => {
+            expect_args(&args, 1)?;
+            rules.push(Rule::ConnectAll { color: parse_color(args[0])? });
+        }
+        "forbiddenPattern" => {
+            expect_args(&args, 1)?;
+            rules.push(Rule::ForbiddenPattern { pattern: parse_pattern(args[0])? });
+        }
+        "minesweeper" => {
+            expect_args(&args, 3)?;
+            let tile = MinesweeperTile {
+                y: parse_number(args[0])?,
+                x: parse_number(args[1])?,
+                number: parse_number(args[2])?,
+            };
+            add_symbol(
+                rules,
+                tile,
+                |r| if let Rule::Minesweeper { tiles } = r { Some(tiles) } else { None },
+                |tiles| Rule::Minesweeper { tiles },
+            );
+        }
+        "number" => {
+            expect_args(&args, 3)?;
+            let tile = AreaNumberTile {
+                y: parse_number(args[0])?,
+                x: parse_number(args[1])?,
+                number: parse_number(args[2])?,
+            };
+            add_symbol(
+                rules,
+                tile,
+                |r| if let Rule::AreaNumber { tiles } = r { Some(tiles) } else { None },
+                |tiles| Rule::AreaNumber { tiles },
+            );
+        }
+        "letter" => {
+            expect_args(&args, 3)?;
+            let tile = LetterTile {
+                y: parse_number(args[0])?,
+                x: parse_number(args[1])?,
+                letter: args[2].to_string(),
+            };
+            add_symbol(
+                rules,
+                tile,
+                |r| if let Rule::Letter { tiles } = r { Some(tiles) } else { None },
+                |tiles| Rule::Letter { tiles },
+            );
+        }
+        "dart" => {
+            expect_args(&args, 4)?;
+            let tile = DartTile {
+                y: parse_number(args[0])?,
+                x: parse_number(args[1])?,
+                orientation: parse_orientation(args[2])?,
+                number: parse_number(args[3])?,
+            };
+            add_symbol(
+                rules,
+                tile,
+                |r| if let Rule::Dart { tiles } = r { Some(tiles) } else { None },
+                |tiles| Rule::Dart { tiles },
+            );
+        }
+        "viewpoint" => {
+            expect_args(&args, 3)?;
+            let tile = ViewpointTile {
+                y: parse_number(args[0])?,
+                x: parse_number(args[1])?,
+                number: parse_number(args[2])?,
+            };
+            add_symbol(
+                rules,
+                tile,
+                |r| if let Rule::Viewpoint { tiles } = r { Some(tiles) } else { None },
+                |tiles| Rule::Viewpoint { tiles },
+            );
+        }
+        "lotus" => {
+            expect_args(&args, 3)?;
+            let tile = LotusTile {
+                y: parse_number(args[0])?,
+                x: parse_number(args[1])?,
+                orientation: parse_orientation(args[2])?,
+            };
+            add_symbol(
+                rules,
+                tile,
+                |r| if let Rule::Lotus { tiles } = r { Some(tiles) } else { None },
+                |tiles| Rule::Lotus { tiles },
+            );
+        }
+        "galaxy" => {
+            expect_args(&args, 2)?;
+            let tile = GalaxyTile { y: parse_number(args[0])?, x: parse_number(args[1])? };
+            add_symbol(
+                rules,
+                tile,
+                |r| if let Rule::Galaxy { tiles } = r { Some(tiles) } else { None },
+                |tiles| Rule::Galaxy { tiles },
+            );
+        }
+        "sameShape" => {
+            expect_args(&args, 1)?;
+            rules.push(Rule::SameShape { color: parse_color(args[0])? });
+        }
+        "uniqueShape" => {
+            expect_args(&args, 1)?;
+            rules.push(Rule::UniqueShape { color: parse_color(args[0])? });
+        }
+        "regionArea" => {
+            expect_args(&args, 2)?;
+            rules.push(Rule::RegionArea { color: parse_color(args[0])?, size: parse_number(args[1])? });
+        }
+        "cellCount" => {
+            expect_args(&args, 2)?;
+            rules.push(Rule::CellCount { color: parse_color(args[0])?, count: parse_number(args[1])? });
+        }
+        "offByX" => {
+            expect_args(&args, 1)?;
+            rules.push(Rule::OffByX { number: parse_number(args[0])? });
+        }
+        "symbolCount" => {
+            expect_args(&args, 3)?;
+            rules.push(Rule::SymbolCount {
+                kind: parse_symbol_count_kind(args[0])?,
+                number: parse_number(args[1])?,
+                color: parse_color(args[2])?,
+            });
+        }
+        "lyingSymbols" => {
+            expect_args(&args, 1)?;
+            rules.push(Rule::LyingSymbols { number: parse_number(args[0])? });
+        }
+        "connect" => {
+            expect_args(&args, 4)?;
+            connections.push(Connection {
+                y1: parse_number(args[0])?,
+                x1: parse_number(args[1])?,
+                y2: parse_number(args[2])?,
+                x2: parse_number(args[3])?,
+            });
+        }
+        _ => return Err(format!("unknown rule: {}", kind)),
+    }
+
+    Ok(())
+}
+
+// Parses a header `WIDTHxHEIGHT`.
+fn parse_header(line: &str) -> Option<(usize, usize)> {
+    let (width, height) = line.trim().split_once('x')?;
+    Some((width.parse().ok()?, height.parse().ok()?))
+}
+
+/// Parses a puzzle in the text notation.
+pub fn parse_puzzle(text: &str) -> Result<Puzzle, ParseError> {
+    let lines = text.lines().collect::<Vec<_>>();
+    let grid_end = lines.iter().position(|line| line.trim_end() == SEPARATOR).unwrap_or(lines.len());
+
+    let header = lines.first().and_then(|line| parse_header(line));
+    let grid_start = if header.is_some() { 1 } else { 0 };
+    let grid_lines = &lines[grid_start..grid_end];
+
+    let width = match header {
+        Some((width, height)) => {
+            if grid_lines.len() != height {
+                return Err(ParseError {
+                    line: grid_end + 1,
+                    message: format!("expected {} rows, found {}", height, grid_lines.len()),
+                });
+            }
+            width
+        }
+        None => grid_lines.iter().map(|line| line.chars().count()).max().unwrap_or(0),
+    };
+    let mut tiles = vec![];
+    for (i, line) in grid_lines.iter().enumerate() {
+        let line_number = grid_start + i + 1;
+        let mut row = vec![];
+        for c in line.chars() {
+            row.push(parse_tile(c).map_err(|message| ParseError { line: line_number, message })?);
+        }
+        if row.len() > width {
+            return Err(ParseError {
+                line: line_number,
+                message: format!("expected at most {} tiles, found {}", width, row.len()),
+            });
+        }
+        while row.len() < width {
+            row.push(Tile { exists: false, fixed: false, color: Color::Undecided });
+        }
+        tiles.push(row);
+    }
+
+    let mut rules = vec![];
+    let mut connections = vec![];
+    for (i, line) in lines.iter().enumerate().skip(grid_end + 1) {
+        parse_line(line, &mut rules, &mut connections).map_err(|message| ParseError { line: i + 1, message })?;
+    }
+
+    Ok(Puzzle {
+        version: CURRENT_VERSION,
+        width,
+        height: tiles.len(),
+        tiles,
+        rules,
+        connections,
+    })
+}
+
+/// Formats a board: `O` for light, `#` for dark, a space for non-existing tiles of `puzzle` and `.` otherwise.
+/// Fails if the size of `board` differs from that of `puzzle`.
+pub fn format_board(puzzle: &Puzzle, board: &Board) -> Result<String, SolverError> {
+    if board.len() != puzzle.height || board.iter().any(|row| row.len() != puzzle.width) {
+        return Err(SolverError::GridSizeMismatch);
+    }
+
+    let mut ret = String::new();
+    for (row, tiles) in board.iter().zip(&puzzle.tiles) {
+        for (cell, tile) in row.iter().zip(tiles) {
+            ret.push(match cell {
+                Some(Color::White) => 'O',
+                Some(Color::Black) => '#',
+                _ if !tile.exists => ' ',
+                _ => '.',
+            });
+        }
+        ret.push('\n');
+    }
+    Ok(ret)
+}
+
+/// Parses a board written as by [`format_board`]. Both `.` and spaces are read as `None`.
+pub fn parse_board(text: &str) -> Result<Board, ParseError> {
+    let mut board = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let mut row = vec![];
+        for c in line.chars() {
+            row.push(match c {
+                'O' => Some(Color::White),
+                '#' => Some(Color::Black),
+                '.' | ' ' => None,
+                _ => {
+                    return Err(ParseError {
+                        line: i + 1,
+                        message: format!("unknown cell: {:?}", c),
+                    })
+                }
+            });
+        }
+        board.push(row);
+    }
+    Ok(board)
+}
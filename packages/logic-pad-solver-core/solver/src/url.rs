@@ -0,0 +1,846 @@
+//! Decoding and encoding of Logic Pad share links (`https://logic-pad.com/...?d=...`).
+//!
+//! The `d` parameter is `<compressor>_<base64>`, where the base64 payload is compressed by the deflate (`dfl`)
+//! or gzip (`gzip`) compressor. The decompressed data is `<serializer version>_<puzzle>`; only version 0 exists,
+//! in which the puzzle is a JSON object whose `grid` is a `|`-separated list of fields:
+//!
+//! - `W<width>` and `H<height>`
+//! - `T<width>:<tiles>`, one character per tile: `w`, `b` and `n` for light, dark and gray tiles (uppercase if
+//!   fixed) and `.` for non-existing tiles
+//! - `C<width>:<groups>`, one character per tile, where adjacent tiles with the same character other than `.`
+//!   are connected
+//! - `R<rules>` and `S<symbols>`, `:`-separated instructions `<id>,<field>=<value>,...` whose values are escaped
+//!   by the JavaScript `escape` function
+//!
+//! Rules which do not affect the solution (e.g. `underclued`) are skipped. As in `jsonify.ts`, non-existing tiles
+//! in forbidden patterns are read as gray tiles and lotus and galaxy coordinates are doubled.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{Read, Write};
+
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+use base64::engine::DecodePaddingMode;
+use base64::Engine;
+use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use crate::puzzle::{
+    add_symbol, AreaNumberTile, Color, Connection, DartTile, GalaxyTile, LetterTile, LotusTile, MinesweeperTile,
+    Orientation, Puzzle, Rule, SymbolCountKind, Tile, ViewpointTile, CURRENT_VERSION,
+};
+
+/// An error in a share link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlError {
+    pub message: String,
+}
+
+impl fmt::Display for UrlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for UrlError {}
+
+fn error<T>(message: impl Into<String>) -> Result<T, UrlError> {
+    Err(UrlError { message: message.into() })
+}
+
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &base64::alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+// Decodes `%XX` and `%uXXXX` as the JavaScript `unescape` function does, i.e. into UTF-16 code units, which matches
+// `escape`. The `d` parameter is percent-encoded ASCII, which `decodeURIComponent` decodes the same way.
+fn unescape(s: &str) -> Result<String, UrlError> {
+    let mut units = vec![];
+    let mut rest = s;
+
+    while let Some(c) = rest.chars().next() {
+        if c != '%' {
+            units.extend_from_slice(c.encode_utf16(&mut [0; 2]));
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        let (len, skip) = if rest[1..].starts_with('u') { (4, 2) } else { (2, 1) };
+        let code = match rest.get(skip..skip + len) {
+            Some(hex) if hex.bytes().all(|b| b.is_ascii_hexdigit()) => u16::from_str_radix(hex, 16).unwrap(),
+            _ => return error(format!("invalid escape sequence in {:?}", s)),
+        };
+        units.push(code);
+        rest = &rest[skip + len..];
+    }
+
+    // A lone surrogate cannot be represented in a Rust string.
+    Ok(char::decode_utf16(units).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)).collect())
+}
+
+fn decompress(data: &str) -> Result<String, UrlError> {
+    let (id, payload) = match data.split_once('_') {
+        Some(split) => split,
+        None => return error("missing compressor"),
+    };
+    // `+` may have been turned into a space if the link was not percent-encoded.
+    let payload = payload.trim().replace(' ', "+");
+    let bytes = match BASE64.decode(payload) {
+        Ok(bytes) => bytes,
+        Err(e) => return error(format!("invalid base64: {}", e)),
+    };
+
+    let mut ret = String::new();
+    let result = match id {
+        // Streams compressed with `CompressionStream("deflate")` have a zlib header; accept raw deflate as well.
+        "dfl" => ZlibDecoder::new(&bytes[..])
+            .read_to_string(&mut ret)
+            .or_else(|_| {
+                ret.clear();
+                DeflateDecoder::new(&bytes[..]).read_to_string(&mut ret)
+            }),
+        "gzip" => GzDecoder::new(&bytes[..]).read_to_string(&mut ret),
+        _ => return error(format!("unknown compressor: {}", id)),
+    };
+    match result {
+        Ok(_) => Ok(ret),
+        Err(e) => error(format!("failed to decompress: {}", e)),
+    }
+}
+
+fn parse_color(s: &str) -> Result<Color, UrlError> {
+    match s {
+        "light" => Ok(Color::White),
+        "dark" => Ok(Color::Black),
+        "gray" => Ok(Color::Undecided),
+        _ => error(format!("unknown color: {}", s)),
+    }
+}
+
+fn parse_orientation(s: &str) -> Result<Orientation, UrlError> {
+    match s {
+        "left" => Ok(Orientation::Left),
+        "right" => Ok(Orientation::Right),
+        "up" => Ok(Orientation::Up),
+        "down" => Ok(Orientation::Down),
+        "up-right" => Ok(Orientation::UpRight),
+        "up-left" => Ok(Orientation::UpLeft),
+        "down-right" => Ok(Orientation::DownRight),
+        "down-left" => Ok(Orientation::DownLeft),
+        _ => error(format!("unknown orientation: {}", s)),
+    }
+}
+
+fn parse_comparison(s: &str) -> Result<SymbolCountKind, UrlError> {
+    match s {
+        "eq" => Ok(SymbolCountKind::Exactly),
+        "ge" => Ok(SymbolCountKind::AtLeast),
+        "le" => Ok(SymbolCountKind::AtMost),
+        _ => error(format!("unknown comparison: {}", s)),
+    }
+}
+
+fn parse_size(s: &str) -> Result<usize, UrlError> {
+    s.parse().or_else(|_| error(format!("invalid size: {}", s)))
+}
+
+fn parse_number(s: &str) -> Result<i32, UrlError> {
+    s.parse().or_else(|_| error(format!("invalid number: {}", s)))
+}
+
+// Symbols are placed at cell centers at integer coordinates, so lotuses and galaxies may be at halves.
+fn parse_coordinate(s: &str) -> Result<f64, UrlError> {
+    match s.parse::<f64>() {
+        Ok(v) if v >= 0.0 => Ok(v),
+        _ => error(format!("invalid coordinate: {}", s)),
+    }
+}
+
+fn parse_cell_coordinate(s: &str) -> Result<usize, UrlError> {
+    Ok(parse_coordinate(s)?.round() as usize)
+}
+
+fn parse_doubled_coordinate(s: &str) -> Result<usize, UrlError> {
+    Ok((parse_coordinate(s)? * 2.0).round() as usize)
+}
+
+// Splits `W<width>:<data>` into the width and one row of characters per `width` characters of data.
+fn parse_rows(field: &str) -> Result<Vec<Vec<char>>, UrlError> {
+    let (width, data) = match field.split_once(':') {
+        Some(split) => split,
+        None => return error(format!("invalid field: {}", field)),
+    };
+    let width = parse_size(width)?;
+    let chars = data.chars().collect::<Vec<_>>();
+    if width == 0 {
+        return if chars.is_empty() { Ok(vec![]) } else { error("tiles with zero width") };
+    }
+    if chars.len() % width != 0 {
+        return error("number of tiles is not a multiple of the width");
+    }
+    Ok(chars.chunks(width).map(|row| row.to_vec()).collect())
+}
+
+fn parse_tile(c: char) -> Result<Tile, UrlError> {
+    let color = match c.to_ascii_lowercase() {
+        '.' => return Ok(Tile { exists: false, fixed: false, color: Color::Undecided }),
+        'w' => Color::White,
+        'b' => Color::Black,
+        'n' => Color::Undecided,
+        _ => return error(format!("unknown tile: {:?}", c)),
+    };
+    Ok(Tile { exists: true, fixed: c.is_ascii_uppercase(), color })
+}
+
+fn parse_connections(field: &str) -> Result<Vec<Connection>, UrlError> {
+    let rows = parse_rows(field)?;
+    let mut connections = vec![];
+    for y in 0..rows.len() {
+        for x in 0..rows[y].len() {
+            let c = rows[y][x];
+            if c == '.' {
+                continue;
+            }
+            if x + 1 < rows[y].len() && rows[y][x + 1] == c {
+                connections.push(Connection { y1: y, x1: x, y2: y, x2: x + 1 });
+            }
+            if y + 1 < rows.len() && rows[y + 1][x] == c {
+                connections.push(Connection { y1: y, x1: x, y2: y + 1, x2: x });
+            }
+        }
+    }
+    Ok(connections)
+}
+
+// An instruction (rule or symbol): `<id>,<field>=<value>,...`.
+struct Instruction<'a> {
+    id: &'a str,
+    fields: HashMap<&'a str, String>,
+}
+
+impl<'a> Instruction<'a> {
+    fn parse(s: &'a str) -> Result<Instruction<'a>, UrlError> {
+        let mut entries = s.split(',');
+        let id = entries.next().unwrap_or("");
+        let mut fields = HashMap::new();
+        for entry in entries {
+            if entry.is_empty() {
+                continue;
+            }
+            match entry.split_once('=') {
+                Some((key, value)) => {
+                    fields.insert(key, unescape(value)?);
+                }
+                None => return error(format!("invalid entry in {}: {}", id, entry)),
+            }
+        }
+        Ok(Instruction { id, fields })
+    }
+
+    fn get(&self, key: &str) -> Result<&str, UrlError> {
+        match self.fields.get(key) {
+            Some(value) => Ok(value),
+            None => error(format!("missing {} in {}", key, self.id)),
+        }
+    }
+}
+
+struct Grid {
+    width: usize,
+    height: usize,
+    tiles: Vec<Vec<Tile>>,
+    connections: Vec<Connection>,
+    rules: Vec<Rule>,
+}
+
+fn parse_rule(instruction: &Instruction) -> Result<Option<Rule>, UrlError> {
+    let rule = match instruction.id {
+        "connect_all" => Rule::ConnectAll { color: parse_color(instruction.get("color")?)? },
+        "banned_pattern" => {
+            let pattern = parse_grid(instruction.get("pattern")?)?.tiles;
+            let pattern = pattern
+                .into_iter()
+                .map(|row| {
+                    row.into_iter()
+                        .map(|tile| {
+                            if tile.exists {
+                                tile
+                            } else {
+                                Tile { exists: true, fixed: false, color: Color::Undecided }
+                            }
+                        })
+                        .collect()
+                })
+                .collect();
+            Rule::ForbiddenPattern { pattern }
+        }
+        "same_shape" => Rule::SameShape { color: parse_color(instruction.get("color")?)? },
+        "unique_shape" => Rule::UniqueShape { color: parse_color(instruction.get("color")?)? },
+        "region_area" => Rule::RegionArea {
+            color: parse_color(instruction.get("color")?)?,
+            size: parse_number(instruction.get("size")?)?,
+        },
+        "cell_count" => Rule::CellCount {
+            color: parse_color(instruction.get("color")?)?,
+            count: parse_number(instruction.get("count")?)?,
+        },
+        "off_by_x" => Rule::OffByX { number: parse_number(instruction.get("number")?)? },
+        "symbols_per_region" => Rule::SymbolCount {
+            number: parse_number(instruction.get("count")?)?,
+            kind: parse_comparison(instruction.get("comparison")?)?,
+            color: parse_color(instruction.get("color")?)?,
+        },
+        "lying_symbols" => Rule::LyingSymbols { number: parse_number(instruction.get("count")?)? },
+        "underclued" => return Ok(None),
+        id => return error(format!("unsupported rule: {}", id)),
+    };
+    Ok(Some(rule))
+}
+
+fn parse_symbol(instruction: &Instruction, rules: &mut Vec<Rule>) -> Result<(), UrlError> {
+    match instruction.id {
+        "minesweeper" => {
+            let tile = MinesweeperTile {
+                y: parse_cell_coordinate(instruction.get("y")?)?,
+                x: parse_cell_coordinate(instruction.get("x")?)?,
+                number: parse_number(instruction.get("number")?)?,
+            };
+            add_symbol(
+                rules,
+                tile,
+                |r| if let Rule::Minesweeper { tiles } = r { Some(tiles) } else { None },
+                |tiles| Rule::Minesweeper { tiles },
+            );
+        }
+        "number" => {
+            let tile = AreaNumberTile {
+                y: parse_cell_coordinate(instruction.get("y")?)?,
+                x: parse_cell_coordinate(instruction.get("x")?)?,
+                number: parse_number(instruction.get("number")?)?,
+            };
+            add_symbol(
+                rules,
+                tile,
+                |r| if let Rule::AreaNumber { tiles } = r { Some(tiles) } else { None },
+                |tiles| Rule::AreaNumber { tiles },
+            );
+        }
+        "letter" => {
+            let tile = LetterTile {
+                y: parse_cell_coordinate(instruction.get("y")?)?,
+                x: parse_cell_coordinate(instruction.get("x")?)?,
+                letter: instruction.get("letter")?.to_string(),
+            };
+            add_symbol(
+                rules,
+                tile,
+                |r| if let Rule::Letter { tiles } = r { Some(tiles) } else { None },
+                |tiles| Rule::Letter { tiles },
+            );
+        }
+        "dart" => {
+            let tile = DartTile {
+                y: parse_cell_coordinate(instruction.get("y")?)?,
+                x: parse_cell_coordinate(instruction.get("x")?)?,
+                orientation: parse_orientation(instruction.get("orientation")?)?,
+                number: parse_number(instruction.get("number")?)?,
+            };
+            add_symbol(
+                rules,
+                tile,
+                |r| if let Rule::Dart { tiles } = r { Some(tiles) } else { None },
+                |tiles| Rule::Dart { tiles },
+            );
+        }
+        "viewpoint" => {
+            let tile = ViewpointTile {
+                y: parse_cell_coordinate(instruction.get("y")?)?,
+                x: parse_cell_coordinate(instruction.get("x")?)?,
+                number: parse_number(instruction.get("number")?)?,
+            };
+            add_symbol(
+                rules,
+                tile,
+                |r| if let Rule::Viewpoint { tiles } = r { Some(tiles) } else { None },
+                |tiles| Rule::Viewpoint { tiles },
+            );
+        }
+        "lotus" => {
+            let tile = LotusTile {
+                y: parse_doubled_coordinate(instruction.get("y")?)?,
+                x: parse_doubled_coordinate(instruction.get("x")?)?,
+                orientation: parse_orientation(instruction.get("orientation")?)?,
+            };
+            add_symbol(
+                rules,
+                tile,
+                |r| if let Rule::Lotus { tiles } = r { Some(tiles) } else { None },
+                |tiles| Rule::Lotus { tiles },
+            );
+        }
+        "galaxy" => {
+            let tile = GalaxyTile {
+                y: parse_doubled_coordinate(instruction.get("y")?)?,
+                x: parse_doubled_coordinate(instruction.get("x")?)?,
+            };
+            add_symbol(
+                rules,
+                tile,
+                |r| if let Rule::Galaxy { tiles } = r { Some(tiles) } else { None },
+                |tiles| Rule::Galaxy { tiles },
+            );
+        }
+        id => return error(format!("unsupported symbol: {}", id)),
+    }
+    Ok(())
+}
+
+fn parse_grid(s: &str) -> Result<Grid, UrlError> {
+    let mut width = None;
+    let mut height = None;
+    let mut tiles = None;
+    let mut connections = vec![];
+    let mut rules = vec![];
+    let mut symbols = vec![];
+
+    for field in s.split('|') {
+        let mut chars = field.chars();
+        let kind = match chars.next() {
+            Some(kind) => kind,
+            None => continue,
+        };
+        let value = chars.as_str();
+        match kind {
+            'W' => width = Some(parse_size(value)?),
+            'H' => height = Some(parse_size(value)?),
+            'T' => {
+                let rows = parse_rows(value)?;
+                tiles = Some(
+                    rows.into_iter()
+                        .map(|row| row.into_iter().map(parse_tile).collect::<Result<Vec<_>, _>>())
+                        .collect::<Result<Vec<_>, _>>()?,
+                );
+            }
+            'C' => connections = parse_connections(value)?,
+            'R' => {
+                for s in value.split(':').filter(|s| !s.is_empty()) {
+                    if let Some(rule) = parse_rule(&Instruction::parse(s)?)? {
+                        rules.push(rule);
+                    }
+                }
+            }
+            'S' => {
+                for s in value.split(':').filter(|s| !s.is_empty()) {
+                    parse_symbol(&Instruction::parse(s)?, &mut symbols)?;
+                }
+            }
+            // Zones and other fields do not affect the solution.
+            _ => (),
+        }
+    }
+
+    let (width, height) = match (width, height) {
+        (Some(width), Some(height)) => (width, height),
+        _ => return error("missing grid size"),
+    };
+    let tiles = tiles.unwrap_or_else(|| {
+        vec![vec![Tile { exists: true, fixed: false, color: Color::Undecided }; width]; height]
+    });
+    rules.extend(symbols);
+
+    Ok(Grid { width, height, tiles, connections, rules })
+}
+
+/// Parses the puzzle in a Logic Pad share link.
+/// Either the whole link or only the value of its `d` parameter may be given.
+pub fn parse_url(url: &str) -> Result<Puzzle, UrlError> {
+    let url = url.trim();
+    let data = match url.split_once("?d=").or_else(|| url.split_once("&d=")) {
+        Some((_, data)) => data.split('&').next().unwrap_or(""),
+        None => url,
+    };
+    let data = unescape(data)?;
+
+    let serialized = decompress(&data)?;
+    let (version, serialized) = match serialized.split_once('_') {
+        Some(split) => split,
+        None => return error("missing serializer version"),
+    };
+    if version != "0" {
+        return error(format!("unsupported serializer version: {}", version));
+    }
+
+    let value: serde_json::Value = match serde_json::from_str(serialized) {
+        Ok(value) => value,
+        Err(e) => return error(format!("invalid puzzle: {}", e)),
+    };
+    let grid = match value.get("grid").and_then(|grid| grid.as_str()) {
+        Some(grid) => parse_grid(grid)?,
+        None => return error("missing grid"),
+    };
+
+    Ok(Puzzle {
+        version: CURRENT_VERSION,
+        width: grid.width,
+        height: grid.height,
+        tiles: grid.tiles,
+        rules: grid.rules,
+        connections: grid.connections,
+    })
+}
+
+// Encodes as the JavaScript `escape` function does.
+fn escape(s: &str) -> String {
+    let mut ret = String::new();
+    for c in s.chars() {
+        if c.is_ascii_alphanumeric() || "@*_+-./".contains(c) {
+            ret.push(c);
+        } else if (c as u32) < 256 {
+            ret.push_str(&format!("%{:02X}", c as u32));
+        } else {
+            let mut buf = [0; 2];
+            for unit in c.encode_utf16(&mut buf) {
+                ret.push_str(&format!("%u{:04X}", unit));
+            }
+        }
+    }
+    ret
+}
+
+// Encodes as the JavaScript `encodeURIComponent` function does.
+fn encode_uri_component(s: &str) -> String {
+    let mut ret = String::new();
+    for &b in s.as_bytes() {
+        if b.is_ascii_alphanumeric() || b"-_.!~*'()".contains(&b) {
+            ret.push(b as char);
+        } else {
+            ret.push_str(&format!("%{:02X}", b));
+        }
+    }
+    ret
+}
+
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::White => "light",
+        Color::Black => "dark",
+        Color::Undecided => "gray",
+    }
+}
+
+fn orientation_name(orientation: Orientation) -> &'static str {
+    match orientation {
+        Orientation::Left => "left",
+        Orientation::Right => "right",
+        Orientation::Up => "up",
+        Orientation::Down => "down",
+        Orientation::UpRight => "up-right",
+        Orientation::UpLeft => "up-left",
+        Orientation::DownRight => "down-right",
+        Orientation::DownLeft => "down-left",
+    }
+}
+
+fn comparison_name(kind: SymbolCountKind) -> &'static str {
+    match kind {
+        SymbolCountKind::Exactly => "eq",
+        SymbolCountKind::AtLeast => "ge",
+        SymbolCountKind::AtMost => "le",
+    }
+}
+
+fn format_doubled_coordinate(v: usize) -> String {
+    if v.is_multiple_of(2) {
+        format!("{}", v / 2)
+    } else {
+        format!("{}.5", v / 2)
+    }
+}
+
+fn format_tile(tile: &Tile) -> char {
+    if !tile.exists {
+        return '.';
+    }
+    let c = match tile.color {
+        Color::White => 'w',
+        Color::Black => 'b',
+        Color::Undecided => 'n',
+    };
+    if tile.fixed {
+        c.to_ascii_uppercase()
+    } else {
+        c
+    }
+}
+
+fn format_tiles(tiles: &[Vec<Tile>]) -> String {
+    let width = tiles.first().map_or(0, |row| row.len());
+    let mut ret = format!("T{}:", width);
+    for row in tiles {
+        ret.extend(row.iter().map(format_tile));
+    }
+    ret
+}
+
+fn find(parent: &mut [usize], i: usize) -> usize {
+    if parent[i] != i {
+        parent[i] = find(parent, parent[i]);
+    }
+    parent[i]
+}
+
+// Indices of the tiles adjacent to the tile at index `i` of a `height` x `width` grid.
+fn neighbors(i: usize, height: usize, width: usize) -> impl Iterator<Item = usize> {
+    let (y, x) = (i / width, i % width);
+    [(y.wrapping_sub(1), x), (y + 1, x), (y, x.wrapping_sub(1)), (y, x + 1)]
+        .into_iter()
+        .filter(move |&(ny, nx)| ny < height && nx < width)
+        .map(move |(ny, nx)| ny * width + nx)
+}
+
+// Tiles in the same group get the same character, which must differ from those of adjacent groups.
+fn format_connections(puzzle: &Puzzle) -> Result<String, UrlError> {
+    let height = puzzle.height;
+    let width = puzzle.width;
+
+    let mut parent = (0..height * width).collect::<Vec<_>>();
+    for conn in &puzzle.connections {
+        if !(conn.y1 < height && conn.x1 < width && conn.y2 < height && conn.x2 < width) {
+            return error("connection out of bounds");
+        }
+        let a = find(&mut parent, conn.y1 * width + conn.x1);
+        let b = find(&mut parent, conn.y2 * width + conn.x2);
+        parent[a] = b;
+    }
+
+    let mut size = vec![0; height * width];
+    for i in 0..height * width {
+        let root = find(&mut parent, i);
+        size[root] += 1;
+    }
+
+    const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let mut group_char: Vec<Option<u8>> = vec![None; height * width];
+    let mut cells = vec![b'.'; height * width];
+    for (i, cell) in cells.iter_mut().enumerate() {
+        let root = find(&mut parent, i);
+        if size[root] == 1 {
+            continue;
+        }
+        if group_char[root].is_none() {
+            // Characters of the other groups adjacent to any tile of this group.
+            let mut used = vec![];
+            for j in 0..height * width {
+                if find(&mut parent, j) != root {
+                    continue;
+                }
+                for k in neighbors(j, height, width) {
+                    let other = find(&mut parent, k);
+                    if other != root {
+                        if let Some(c) = group_char[other] {
+                            used.push(c);
+                        }
+                    }
+                }
+            }
+            match CHARS.iter().find(|c| !used.contains(c)) {
+                Some(&c) => group_char[root] = Some(c),
+                None => return error("too many adjacent connection groups"),
+            }
+        }
+        *cell = group_char[root].unwrap();
+    }
+
+    // Logic Pad only connects adjacent tiles, so each group must be contiguous.
+    for i in 0..height * width {
+        let root = find(&mut parent, i);
+        if size[root] == 1 || root != i {
+            continue;
+        }
+        let mut visited = vec![false; height * width];
+        let mut stack = vec![root];
+        visited[root] = true;
+        let mut count = 0;
+        while let Some(j) = stack.pop() {
+            count += 1;
+            for k in neighbors(j, height, width) {
+                if !visited[k] && find(&mut parent, k) == root {
+                    visited[k] = true;
+                    stack.push(k);
+                }
+            }
+        }
+        if count != size[root] {
+            return error("connected tiles must be adjacent");
+        }
+    }
+
+    let mut ret = format!("C{}:", width);
+    ret.extend(cells.into_iter().map(|c| c as char));
+    Ok(ret)
+}
+
+fn format_instruction(id: &str, fields: &[(&str, String)]) -> String {
+    let mut ret = id.to_string();
+    for (key, value) in fields {
+        ret.push_str(&format!(",{}={}", key, escape(value)));
+    }
+    ret
+}
+
+fn format_pattern(pattern: &[Vec<Tile>]) -> String {
+    let height = pattern.len();
+    let width = pattern.first().map_or(0, |row| row.len());
+    format!("W{}|H{}|{}|C{}:{}|R|S", width, height, format_tiles(pattern), width, ".".repeat(width * height))
+}
+
+fn format_grid(puzzle: &Puzzle) -> Result<String, UrlError> {
+    let mut rules = vec![];
+    let mut symbols = vec![];
+
+    for rule in &puzzle.rules {
+        match rule {
+            Rule::ConnectAll { color } => {
+                rules.push(format_instruction("connect_all", &[("color", color_name(*color).to_string())]));
+            }
+            Rule::ForbiddenPattern { pattern } => {
+                rules.push(format_instruction("banned_pattern", &[("pattern", format_pattern(pattern))]));
+            }
+            Rule::Minesweeper { tiles } => {
+                for t in tiles {
+                    symbols.push(format_instruction(
+                        "minesweeper",
+                        &[("x", t.x.to_string()), ("y", t.y.to_string()), ("number", t.number.to_string())],
+                    ));
+                }
+            }
+            Rule::AreaNumber { tiles } => {
+                for t in tiles {
+                    symbols.push(format_instruction(
+                        "number",
+                        &[("x", t.x.to_string()), ("y", t.y.to_string()), ("number", t.number.to_string())],
+                    ));
+                }
+            }
+            Rule::Letter { tiles } => {
+                for t in tiles {
+                    symbols.push(format_instruction(
+                        "letter",
+                        &[("x", t.x.to_string()), ("y", t.y.to_string()), ("letter", t.letter.clone())],
+                    ));
+                }
+            }
+            Rule::Dart { tiles } => {
+                for t in tiles {
+                    symbols.push(format_instruction(
+                        "dart",
+                        &[
+                            ("x", t.x.to_string()),
+                            ("y", t.y.to_string()),
+                            ("number", t.number.to_string()),
+                            ("orientation", orientation_name(t.orientation).to_string()),
+                        ],
+                    ));
+                }
+            }
+            Rule::Viewpoint { tiles } => {
+                for t in tiles {
+                    symbols.push(format_instruction(
+                        "viewpoint",
+                        &[("x", t.x.to_string()), ("y", t.y.to_string()), ("number", t.number.to_string())],
+                    ));
+                }
+            }
+            Rule::Lotus { tiles } => {
+                for t in tiles {
+                    symbols.push(format_instruction(
+                        "lotus",
+                        &[
+                            ("x", format_doubled_coordinate(t.x)),
+                            ("y", format_doubled_coordinate(t.y)),
+                            ("orientation", orientation_name(t.orientation).to_string()),
+                        ],
+                    ));
+                }
+            }
+            Rule::Galaxy { tiles } => {
+                for t in tiles {
+                    symbols.push(format_instruction(
+                        "galaxy",
+                        &[("x", format_doubled_coordinate(t.x)), ("y", format_doubled_coordinate(t.y))],
+                    ));
+                }
+            }
+            Rule::SameShape { color } => {
+                rules.push(format_instruction("same_shape", &[("color", color_name(*color).to_string())]));
+            }
+            Rule::UniqueShape { color } => {
+                rules.push(format_instruction("unique_shape", &[("color", color_name(*color).to_string())]));
+            }
+            Rule::RegionArea { color, size } => {
+                rules.push(format_instruction(
+                    "region_area",
+                    &[("color", color_name(*color).to_string()), ("size", size.to_string())],
+                ));
+            }
+            Rule::CellCount { color, count } => {
+                rules.push(format_instruction(
+                    "cell_count",
+                    &[("color", color_name(*color).to_string()), ("count", count.to_string())],
+                ));
+            }
+            Rule::OffByX { number } => {
+                rules.push(format_instruction("off_by_x", &[("number", number.to_string())]));
+            }
+            Rule::SymbolCount { number, kind, color } => {
+                rules.push(format_instruction(
+                    "symbols_per_region",
+                    &[
+                        ("color", color_name(*color).to_string()),
+                        ("count", number.to_string()),
+                        ("comparison", comparison_name(*kind).to_string()),
+                    ],
+                ));
+            }
+            Rule::LyingSymbols { number } => {
+                rules.push(format_instruction("lying_symbols", &[("count", number.to_string())]));
+            }
+        }
+    }
+
+    Ok(format!(
+        "W{}|H{}|{}|{}|R{}|S{}",
+        puzzle.width,
+        puzzle.height,
+        format_tiles(&puzzle.tiles),
+        format_connections(puzzle)?,
+        rules.join(":"),
+        symbols.join(":")
+    ))
+}
+
+/// Encodes `puzzle` as a Logic Pad share link which opens it in the editor.
+/// Fails if the puzzle has connections which cannot be represented in Logic Pad,
+/// i.e. connected tiles which are not joined by a chain of adjacent connected tiles.
+pub fn to_url(puzzle: &Puzzle) -> Result<String, UrlError> {
+    let serialized = serde_json::json!({
+        "title": "",
+        "grid": format_grid(puzzle)?,
+        "solution": null,
+        "difficulty": 0,
+        "author": "",
+        "description": "",
+        "link": "",
+    });
+    let data = format!("0_{}", serialized);
+
+    let mut encoder = ZlibEncoder::new(vec![], Compression::best());
+    encoder.write_all(data.as_bytes()).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    Ok(format!(
+        "https://logic-pad.com/create?d={}",
+        encode_uri_component(&format!("dfl_{}", BASE64.encode(compressed)))
+    ))
+}
@@ -0,0 +1,213 @@
+use crate::error::SolverError;
+use crate::puzzle::{Color, Orientation, Puzzle, Rule};
+
+fn check_color(rule: usize, color: Color, problems: &mut Vec<SolverError>) {
+    if color == Color::Undecided {
+        problems.push(SolverError::GrayColor { rule });
+    }
+}
+
+// Checks a symbol placed on a cell (i.e. not on doubled coordinates).
+fn check_cell_symbol(puzzle: &Puzzle, rule: usize, y: usize, x: usize, problems: &mut Vec<SolverError>) {
+    if !(y < puzzle.height && x < puzzle.width) {
+        problems.push(SolverError::SymbolOutOfBounds { rule, y, x });
+    } else if !puzzle.tiles[y][x].exists {
+        problems.push(SolverError::SymbolOnNonExistingTile { rule, y, x });
+    }
+}
+
+// Checks a lotus or galaxy, whose coordinates are doubled.
+fn check_doubled_symbol(puzzle: &Puzzle, rule: usize, y: usize, x: usize, problems: &mut Vec<SolverError>) -> bool {
+    if !(y / 2 < puzzle.height && x / 2 < puzzle.width) {
+        problems.push(SolverError::SymbolOutOfBounds { rule, y, x });
+        return false;
+    }
+    true
+}
+
+/// Reports every problem of `puzzle` which would make encoding fail, or which would make the encoder panic.
+/// The tiles are checked first; if their dimensions are inconsistent, the remaining checks are skipped.
+pub fn validate(puzzle: &Puzzle) -> Vec<SolverError> {
+    let mut problems = vec![];
+
+    let height = puzzle.height;
+    let width = puzzle.width;
+
+    if height == 0 || width == 0 {
+        problems.push(SolverError::InvalidSize);
+        return problems;
+    }
+    if puzzle.tiles.len() != height {
+        problems.push(SolverError::TileRowCountMismatch);
+        return problems;
+    }
+    for y in 0..height {
+        if puzzle.tiles[y].len() != width {
+            problems.push(SolverError::TileColumnCountMismatch { y });
+        }
+    }
+    if !problems.is_empty() {
+        return problems;
+    }
+
+    for y in 0..height {
+        for x in 0..width {
+            let tile = &puzzle.tiles[y][x];
+            if tile.exists && tile.fixed && tile.color == Color::Undecided {
+                problems.push(SolverError::FixedGrayTile { y, x });
+            }
+        }
+    }
+
+    for (i, conn) in puzzle.connections.iter().enumerate() {
+        if !(conn.y1 < height && conn.x1 < width && conn.y2 < height && conn.x2 < width) {
+            problems.push(SolverError::ConnectionOutOfBounds { connection: i });
+        }
+    }
+
+    let has_symbol_count = puzzle.rules.iter().any(|rule| matches!(rule, Rule::SymbolCount { .. }));
+    let mut has_off_by_x = false;
+    let mut has_area_number = false;
+    let mut has_lying_symbols = false;
+    let mut has_region_area_white = false;
+    let mut has_region_area_black = false;
+
+    for (i, rule) in puzzle.rules.iter().enumerate() {
+        match rule {
+            Rule::ConnectAll { color } | Rule::SameShape { color } | Rule::UniqueShape { color } => {
+                check_color(i, *color, &mut problems);
+            }
+            Rule::ForbiddenPattern { pattern } => {
+                if pattern.is_empty() || pattern.iter().any(|row| row.len() != pattern[0].len()) {
+                    problems.push(SolverError::InvalidForbiddenPattern { rule: i });
+                } else if pattern.iter().flatten().all(|tile| tile.color == Color::Undecided) {
+                    problems.push(SolverError::EmptyForbiddenPattern { rule: i });
+                }
+            }
+            Rule::Minesweeper { tiles } => {
+                for tile in tiles {
+                    check_cell_symbol(puzzle, i, tile.y, tile.x, &mut problems);
+                }
+            }
+            Rule::AreaNumber { tiles } => {
+                if has_area_number {
+                    problems.push(SolverError::MultipleAreaNumberRules { rule: i });
+                }
+                has_area_number = true;
+
+                let mut seen = vec![vec![false; width]; height];
+                for tile in tiles {
+                    if !(tile.y < height && tile.x < width) {
+                        problems.push(SolverError::SymbolOutOfBounds { rule: i, y: tile.y, x: tile.x });
+                        continue;
+                    }
+                    if seen[tile.y][tile.x] {
+                        problems.push(SolverError::DuplicateAreaNumber { rule: i, y: tile.y, x: tile.x });
+                    }
+                    seen[tile.y][tile.x] = true;
+                }
+            }
+            Rule::Letter { tiles } => {
+                for tile in tiles {
+                    check_cell_symbol(puzzle, i, tile.y, tile.x, &mut problems);
+                }
+            }
+            Rule::Dart { tiles } => {
+                for tile in tiles {
+                    check_cell_symbol(puzzle, i, tile.y, tile.x, &mut problems);
+                }
+            }
+            Rule::Viewpoint { tiles } => {
+                for tile in tiles {
+                    check_cell_symbol(puzzle, i, tile.y, tile.x, &mut problems);
+                }
+            }
+            Rule::Lotus { tiles } => {
+                for tile in tiles {
+                    if !check_doubled_symbol(puzzle, i, tile.y, tile.x, &mut problems) {
+                        continue;
+                    }
+                    let valid = match tile.orientation {
+                        Orientation::Down | Orientation::Up => tile.y % 2 == 0,
+                        Orientation::Left | Orientation::Right => tile.x % 2 == 0,
+                        _ => tile.y % 2 == 0 && tile.x % 2 == 0,
+                    };
+                    if !valid {
+                        problems.push(SolverError::InvalidLotusPosition { rule: i, y: tile.y, x: tile.x });
+                    } else if has_symbol_count && !(tile.y % 2 == 0 && tile.x % 2 == 0) {
+                        problems.push(SolverError::SymbolNotOnCellCenter { rule: i, y: tile.y, x: tile.x });
+                    }
+                }
+            }
+            Rule::Galaxy { tiles } => {
+                for tile in tiles {
+                    if !check_doubled_symbol(puzzle, i, tile.y, tile.x, &mut problems) {
+                        continue;
+                    }
+                    if tile.y % 2 == 1 && tile.x % 2 == 1 {
+                        problems.push(SolverError::GalaxyOnCorner { rule: i, y: tile.y, x: tile.x });
+                    } else if has_symbol_count && !(tile.y % 2 == 0 && tile.x % 2 == 0) {
+                        problems.push(SolverError::SymbolNotOnCellCenter { rule: i, y: tile.y, x: tile.x });
+                    }
+                }
+            }
+            Rule::RegionArea { color, size } => {
+                let seen = match *color {
+                    Color::White => &mut has_region_area_white,
+                    Color::Black => &mut has_region_area_black,
+                    Color::Undecided => {
+                        problems.push(SolverError::GrayColor { rule: i });
+                        continue;
+                    }
+                };
+                if *seen {
+                    problems.push(SolverError::MultipleRegionAreaRules { rule: i });
+                }
+                *seen = true;
+                if *size < 1 {
+                    problems.push(SolverError::InvalidNumber { rule: i });
+                }
+            }
+            Rule::CellCount { color, count } => {
+                check_color(i, *color, &mut problems);
+                if *count < 0 {
+                    problems.push(SolverError::InvalidNumber { rule: i });
+                }
+            }
+            Rule::OffByX { number } => {
+                if has_off_by_x {
+                    problems.push(SolverError::MultipleOffByX { rule: i });
+                }
+                has_off_by_x = true;
+                if *number < 0 {
+                    problems.push(SolverError::NegativeOffByX { rule: i });
+                }
+            }
+            Rule::SymbolCount { number, kind: _, color } => {
+                check_color(i, *color, &mut problems);
+                if *number < 0 {
+                    problems.push(SolverError::InvalidNumber { rule: i });
+                }
+            }
+            Rule::LyingSymbols { number } => {
+                if has_lying_symbols {
+                    problems.push(SolverError::MultipleLyingSymbolsRules { rule: i });
+                }
+                has_lying_symbols = true;
+                if *number < 0 {
+                    problems.push(SolverError::InvalidNumber { rule: i });
+                }
+            }
+        }
+    }
+
+    problems
+}
+
+/// Returns the first problem reported by `validate`, if any.
+pub fn check(puzzle: &Puzzle) -> Result<(), SolverError> {
+    match validate(puzzle).into_iter().next() {
+        Some(problem) => Err(problem),
+        None => Ok(()),
+    }
+}
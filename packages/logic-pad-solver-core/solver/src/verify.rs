@@ -0,0 +1,431 @@
+//! Checking a colored grid against a puzzle without the SAT solver.
+//!
+//! The checks are written directly from the rules with plain graph algorithms, so that they can serve as an
+//! oracle for the encodings in [`crate::solver`].
+
+use serde::Serialize;
+
+use crate::error::SolverError;
+use crate::puzzle::{Color, Orientation, Puzzle, Rule, SymbolCountKind, Tile};
+use crate::solver::Board;
+use crate::validate;
+
+/// A part of the puzzle which the grid does not satisfy.
+/// Coordinates of symbols are the same as in the input (i.e. doubled for lotuses and galaxies).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "type")]
+pub enum Violation {
+    /// An existing tile which is not colored, or a fixed tile colored differently.
+    #[serde(rename = "tile")]
+    Tile { y: usize, x: usize },
+    #[serde(rename = "connection")]
+    Connection { connection: usize },
+    #[serde(rename = "rule")]
+    Rule { rule: usize },
+    #[serde(rename = "symbol")]
+    Symbol { rule: usize, index: usize, y: usize, x: usize },
+}
+
+// Maximal 4-connected sets of existing tiles of the same color.
+pub(crate) struct Regions {
+    pub(crate) id: Vec<Vec<Option<usize>>>,
+    pub(crate) cells: Vec<Vec<(usize, usize)>>,
+    color: Vec<Color>,
+}
+
+impl Regions {
+    pub(crate) fn new(grid: &[Vec<Option<Color>>]) -> Regions {
+        let height = grid.len();
+        let width = grid[0].len();
+
+        let mut id = vec![vec![None; width]; height];
+        let mut cells = vec![];
+        let mut color = vec![];
+
+        for y in 0..height {
+            for x in 0..width {
+                let c = match grid[y][x] {
+                    Some(c) if id[y][x].is_none() => c,
+                    _ => continue,
+                };
+
+                let r = cells.len();
+                let mut region = vec![(y, x)];
+                id[y][x] = Some(r);
+                let mut i = 0;
+                while i < region.len() {
+                    let (y, x) = region[i];
+                    i += 1;
+                    for (ny, nx) in neighbors(y, x, height, width) {
+                        if grid[ny][nx] == Some(c) && id[ny][nx].is_none() {
+                            id[ny][nx] = Some(r);
+                            region.push((ny, nx));
+                        }
+                    }
+                }
+
+                cells.push(region);
+                color.push(c);
+            }
+        }
+
+        Regions { id, cells, color }
+    }
+
+    fn of_color(&self, c: Color) -> impl Iterator<Item = &Vec<(usize, usize)>> {
+        self.cells.iter().zip(&self.color).filter(move |&(_, &color)| color == c).map(|(cells, _)| cells)
+    }
+}
+
+fn neighbors(y: usize, x: usize, height: usize, width: usize) -> Vec<(usize, usize)> {
+    let mut ret = vec![];
+    if y > 0 {
+        ret.push((y - 1, x));
+    }
+    if y + 1 < height {
+        ret.push((y + 1, x));
+    }
+    if x > 0 {
+        ret.push((y, x - 1));
+    }
+    if x + 1 < width {
+        ret.push((y, x + 1));
+    }
+    ret
+}
+
+pub(crate) fn opposite(color: Color) -> Color {
+    match color {
+        Color::White => Color::Black,
+        Color::Black => Color::White,
+        Color::Undecided => Color::Undecided,
+    }
+}
+
+fn matches_number(value: i32, number: i32, off_by: Option<i32>) -> bool {
+    match off_by {
+        Some(off_by) => value == number + off_by || value == number - off_by,
+        None => value == number,
+    }
+}
+
+// The `t`-th of the 8 rotations and reflections.
+fn transform(t: usize, (y, x): (i32, i32)) -> (i32, i32) {
+    let (y, x) = if t & 4 != 0 { (x, y) } else { (y, x) };
+    (if t & 2 != 0 { -y } else { y }, if t & 1 != 0 { -x } else { x })
+}
+
+fn canonical_shape(cells: &[(usize, usize)]) -> Vec<(i32, i32)> {
+    let mut ret = vec![];
+    for t in 0..8 {
+        let mut shape = cells.iter().map(|&(y, x)| transform(t, (y as i32, x as i32))).collect::<Vec<_>>();
+        let ymin = shape.iter().map(|&(y, _)| y).min().unwrap();
+        let xmin = shape.iter().map(|&(_, x)| x).min().unwrap();
+        for p in &mut shape {
+            *p = (p.0 - ymin, p.1 - xmin);
+        }
+        shape.sort();
+        if t == 0 || shape < ret {
+            ret = shape;
+        }
+    }
+    ret
+}
+
+fn contains_pattern(grid: &[Vec<Option<Color>>], pattern: &[Vec<Tile>]) -> bool {
+    let mut cells = vec![];
+    for (y, row) in pattern.iter().enumerate() {
+        for (x, tile) in row.iter().enumerate() {
+            if tile.color != Color::Undecided {
+                cells.push(((y as i32, x as i32), tile.color));
+            }
+        }
+    }
+    let height = grid.len() as i32;
+    let width = grid[0].len() as i32;
+
+    for t in 0..8 {
+        let image = cells.iter().map(|&(p, _)| transform(t, p)).collect::<Vec<_>>();
+        let ymin = image.iter().map(|&(y, _)| y).min().unwrap();
+        let xmin = image.iter().map(|&(_, x)| x).min().unwrap();
+        let ymax = image.iter().map(|&(y, _)| y).max().unwrap();
+        let xmax = image.iter().map(|&(_, x)| x).max().unwrap();
+
+        for oy in -ymin..(height - ymax) {
+            for ox in -xmin..(width - xmax) {
+                let found = image.iter().zip(&cells).all(|(&(y, x), &(_, color))| {
+                    grid[(y + oy) as usize][(x + ox) as usize] == Some(color)
+                });
+                if found {
+                    return true;
+                }
+            }
+        }
+    }
+
+    false
+}
+
+pub(crate) fn ray(y: usize, x: usize, height: usize, width: usize, orientation: Orientation) -> Vec<(usize, usize)> {
+    let (dy, dx) = match orientation {
+        Orientation::Up => (-1, 0),
+        Orientation::Down => (1, 0),
+        Orientation::Left => (0, -1),
+        Orientation::Right => (0, 1),
+        Orientation::UpLeft => (-1, -1),
+        Orientation::UpRight => (-1, 1),
+        Orientation::DownLeft => (1, -1),
+        Orientation::DownRight => (1, 1),
+    };
+    let mut ret = vec![];
+    let (mut y, mut x) = (y as i32 + dy, x as i32 + dx);
+    while 0 <= y && y < height as i32 && 0 <= x && x < width as i32 {
+        ret.push((y as usize, x as usize));
+        y += dy;
+        x += dx;
+    }
+    ret
+}
+
+// Checks that the region of the cell containing the center `(sy, sx)` (in doubled coordinates) is mapped onto
+// itself by the symmetry of a lotus (`orientation` is `Some`) or a galaxy (`None`).
+pub(crate) fn is_symmetric(regions: &Regions, sy: usize, sx: usize, orientation: Option<Orientation>) -> bool {
+    let height = regions.id.len() as i32;
+    let width = regions.id[0].len() as i32;
+
+    let r = match regions.id[sy / 2][sx / 2] {
+        Some(r) => r,
+        None => return false,
+    };
+    let (sy, sx) = (sy as i32, sx as i32);
+
+    regions.cells[r].iter().all(|&(y, x)| {
+        let (y, x) = (y as i32, x as i32);
+        // Doubled coordinates of the image of the center of the cell.
+        let (y2, x2) = match orientation {
+            None => (2 * sy - 2 * y, 2 * sx - 2 * x),
+            Some(Orientation::Up) | Some(Orientation::Down) => (2 * y, 2 * sx - 2 * x),
+            Some(Orientation::Left) | Some(Orientation::Right) => (2 * sy - 2 * y, 2 * x),
+            Some(Orientation::UpRight) | Some(Orientation::DownLeft) => (sy + sx - 2 * x, sy + sx - 2 * y),
+            Some(Orientation::UpLeft) | Some(Orientation::DownRight) => (sy - sx + 2 * x, sx - sy + 2 * y),
+        };
+        let (y2, x2) = (y2 / 2, x2 / 2);
+        0 <= y2 && y2 < height && 0 <= x2 && x2 < width && regions.id[y2 as usize][x2 as usize] == Some(r)
+    })
+}
+
+fn check_symbol(violations: &mut Vec<Violation>, rule: usize, index: usize, y: usize, x: usize, ok: bool) {
+    if !ok {
+        violations.push(Violation::Symbol { rule, index, y, x });
+    }
+}
+
+/// Checks `grid` against every tile, connection and rule of `puzzle` and returns everything it violates.
+///
+/// Non-existing tiles of `puzzle` are ignored in `grid`. Existing tiles which are `None` (or gray) in `grid`
+/// are reported as [`Violation::Tile`] and are treated as holes by the remaining checks.
+pub fn verify(puzzle: &Puzzle, grid: &Board) -> Result<Vec<Violation>, SolverError> {
+    validate::check(puzzle)?;
+    if grid.len() != puzzle.height || grid.iter().any(|row| row.len() != puzzle.width) {
+        return Err(SolverError::GridSizeMismatch);
+    }
+
+    let height = puzzle.height;
+    let width = puzzle.width;
+    let mut violations = vec![];
+
+    let mut cells = vec![vec![None; width]; height];
+    for y in 0..height {
+        for x in 0..width {
+            let tile = &puzzle.tiles[y][x];
+            if !tile.exists {
+                continue;
+            }
+            match grid[y][x] {
+                Some(color) if color != Color::Undecided => {
+                    if tile.fixed && tile.color != color {
+                        violations.push(Violation::Tile { y, x });
+                    }
+                    cells[y][x] = Some(color);
+                }
+                _ => violations.push(Violation::Tile { y, x }),
+            }
+        }
+    }
+    let grid = cells;
+
+    for (i, conn) in puzzle.connections.iter().enumerate() {
+        if grid[conn.y1][conn.x1] != grid[conn.y2][conn.x2] {
+            violations.push(Violation::Connection { connection: i });
+        }
+    }
+
+    let regions = Regions::new(&grid);
+    let off_by = puzzle.rules.iter().find_map(|rule| match *rule {
+        Rule::OffByX { number } if number > 0 => Some(number),
+        _ => None,
+    });
+
+    // Cells of all symbols, for symbolCount.
+    let mut symbol_cells = vec![];
+
+    for (i, rule) in puzzle.rules.iter().enumerate() {
+        match rule {
+            Rule::ConnectAll { color } => {
+                if regions.of_color(*color).count() > 1 {
+                    violations.push(Violation::Rule { rule: i });
+                }
+            }
+            Rule::ForbiddenPattern { pattern } => {
+                if contains_pattern(&grid, pattern) {
+                    violations.push(Violation::Rule { rule: i });
+                }
+            }
+            Rule::Minesweeper { tiles } => {
+                for (index, t) in tiles.iter().enumerate() {
+                    symbol_cells.push((t.y, t.x));
+                    let ok = match grid[t.y][t.x] {
+                        Some(color) => {
+                            let n = grid[t.y.saturating_sub(1)..(t.y + 2).min(height)]
+                                .iter()
+                                .flat_map(|row| &row[t.x.saturating_sub(1)..(t.x + 2).min(width)])
+                                .filter(|&&cell| cell == Some(opposite(color)))
+                                .count();
+                            matches_number(n as i32, t.number, off_by)
+                        }
+                        None => true,
+                    };
+                    check_symbol(&mut violations, i, index, t.y, t.x, ok);
+                }
+            }
+            Rule::AreaNumber { tiles } => {
+                for (index, t) in tiles.iter().enumerate() {
+                    symbol_cells.push((t.y, t.x));
+                    // A number on a non-existing tile is a region of its own.
+                    let ok = match regions.id[t.y][t.x] {
+                        Some(r) => matches_number(regions.cells[r].len() as i32, t.number, off_by),
+                        None if !puzzle.tiles[t.y][t.x].exists => matches_number(1, t.number, off_by),
+                        None => true,
+                    };
+                    check_symbol(&mut violations, i, index, t.y, t.x, ok);
+                }
+            }
+            Rule::Letter { tiles } => {
+                for (index, t) in tiles.iter().enumerate() {
+                    symbol_cells.push((t.y, t.x));
+                    let r = regions.id[t.y][t.x];
+                    // Same letters must be in the same region, and different letters in different regions.
+                    let ok = r.is_none()
+                        || tiles.iter().all(|u| (u.letter == t.letter) == (regions.id[u.y][u.x] == r));
+                    check_symbol(&mut violations, i, index, t.y, t.x, ok);
+                }
+            }
+            Rule::Dart { tiles } => {
+                for (index, t) in tiles.iter().enumerate() {
+                    symbol_cells.push((t.y, t.x));
+                    let ok = match grid[t.y][t.x] {
+                        Some(color) => {
+                            let n = ray(t.y, t.x, height, width, t.orientation)
+                                .into_iter()
+                                .filter(|&(y, x)| grid[y][x] == Some(opposite(color)))
+                                .count();
+                            matches_number(n as i32, t.number, off_by)
+                        }
+                        None => true,
+                    };
+                    check_symbol(&mut violations, i, index, t.y, t.x, ok);
+                }
+            }
+            Rule::Viewpoint { tiles } => {
+                for (index, t) in tiles.iter().enumerate() {
+                    symbol_cells.push((t.y, t.x));
+                    let ok = match grid[t.y][t.x] {
+                        Some(color) => {
+                            let mut n = 1;
+                            for d in [Orientation::Up, Orientation::Down, Orientation::Left, Orientation::Right] {
+                                n += ray(t.y, t.x, height, width, d)
+                                    .into_iter()
+                                    .take_while(|&(y, x)| grid[y][x] == Some(color))
+                                    .count();
+                            }
+                            matches_number(n as i32, t.number, off_by)
+                        }
+                        None => true,
+                    };
+                    check_symbol(&mut violations, i, index, t.y, t.x, ok);
+                }
+            }
+            Rule::Lotus { tiles } => {
+                for (index, t) in tiles.iter().enumerate() {
+                    symbol_cells.push((t.y / 2, t.x / 2));
+                    check_symbol(&mut violations, i, index, t.y, t.x, is_symmetric(&regions, t.y, t.x, Some(t.orientation)));
+                }
+            }
+            Rule::Galaxy { tiles } => {
+                for (index, t) in tiles.iter().enumerate() {
+                    symbol_cells.push((t.y / 2, t.x / 2));
+                    check_symbol(&mut violations, i, index, t.y, t.x, is_symmetric(&regions, t.y, t.x, None));
+                }
+            }
+            Rule::SameShape { color } => {
+                let mut shapes = regions.of_color(*color).map(|cells| canonical_shape(cells));
+                if let Some(first) = shapes.next() {
+                    if shapes.any(|shape| shape != first) {
+                        violations.push(Violation::Rule { rule: i });
+                    }
+                }
+            }
+            Rule::UniqueShape { color } => {
+                let mut shapes = regions.of_color(*color).map(|cells| canonical_shape(cells)).collect::<Vec<_>>();
+                let n = shapes.len();
+                shapes.sort();
+                shapes.dedup();
+                if shapes.len() != n {
+                    violations.push(Violation::Rule { rule: i });
+                }
+            }
+            Rule::RegionArea { color, size } => {
+                if regions.of_color(*color).any(|cells| cells.len() as i32 != *size) {
+                    violations.push(Violation::Rule { rule: i });
+                }
+            }
+            Rule::CellCount { color, count } => {
+                let n = grid.iter().flatten().filter(|&&c| c == Some(*color)).count();
+                if n as i32 != *count {
+                    violations.push(Violation::Rule { rule: i });
+                }
+            }
+            Rule::OffByX { .. } | Rule::SymbolCount { .. } | Rule::LyingSymbols { .. } => (),
+        }
+    }
+
+    for (i, rule) in puzzle.rules.iter().enumerate() {
+        if let &Rule::SymbolCount { number, kind, color } = rule {
+            let ok = regions.of_color(color).all(|cells| {
+                let r = regions.id[cells[0].0][cells[0].1];
+                let n = symbol_cells.iter().filter(|&&(y, x)| regions.id[y][x] == r).count() as i32;
+                match kind {
+                    SymbolCountKind::Exactly => n == number,
+                    SymbolCountKind::AtLeast => n >= number,
+                    SymbolCountKind::AtMost => n <= number,
+                }
+            });
+            if !ok {
+                violations.push(Violation::Rule { rule: i });
+            }
+        }
+    }
+
+    // False symbols are not violations by themselves under lyingSymbols, but their number must be exact.
+    for (i, rule) in puzzle.rules.iter().enumerate() {
+        if let &Rule::LyingSymbols { number } = rule {
+            let lying = violations.iter().filter(|v| matches!(v, Violation::Symbol { .. })).count();
+            violations.retain(|v| !matches!(v, Violation::Symbol { .. }));
+            if lying as i32 != number {
+                violations.push(Violation::Rule { rule: i });
+            }
+        }
+    }
+
+    Ok(violations)
+}
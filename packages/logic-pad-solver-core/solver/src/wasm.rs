@@ -0,0 +1,97 @@
+// Exports for the wasm32-unknown-unknown build with wasm-bindgen (enabled by the `wasm-bindgen` feature).
+// They take and return the same JSON as the C ABI exports, as JS strings; `src/bindgenBridge.js` wraps them with
+// the typed API of `src/solverBridge.js`.
+
+use wasm_bindgen::prelude::*;
+
+use std::time::Duration;
+
+use crate::{json, solver};
+
+#[wasm_bindgen(js_name = solvePuzzle)]
+pub fn solve_puzzle(data: &str, underclued: bool) -> String {
+    json::solve(data.as_bytes(), underclued)
+}
+
+// `time_limit_ms` is measured with `Date.now()` of the JS host.
+#[wasm_bindgen(js_name = solvePuzzleWithBudget)]
+pub fn solve_puzzle_with_budget(data: &str, time_limit_ms: Option<u32>, max_solver_calls: Option<usize>) -> String {
+    let budget = solver::Budget {
+        time_limit: time_limit_ms.map(|ms| Duration::from_millis(ms as u64)),
+        max_solver_calls,
+    };
+    json::solve_with_budget(data.as_bytes(), &budget)
+}
+
+// `on_fact` is called with the JSON of each proven cell.
+#[wasm_bindgen(js_name = solvePuzzleWithProgress)]
+pub fn solve_puzzle_with_progress(
+    data: &str,
+    time_limit_ms: Option<u32>,
+    max_solver_calls: Option<usize>,
+    on_fact: &js_sys::Function,
+) -> String {
+    let budget = solver::Budget {
+        time_limit: time_limit_ms.map(|ms| Duration::from_millis(ms as u64)),
+        max_solver_calls,
+    };
+    json::solve_with_progress(data.as_bytes(), &budget, |fact| {
+        // Exceptions thrown by the callback are ignored.
+        let _ = on_fact.call1(&JsValue::NULL, &JsValue::from_str(&fact));
+    })
+}
+
+#[wasm_bindgen(js_name = enumerateSolutions)]
+pub fn enumerate_solutions(data: &str, limit: usize) -> String {
+    json::enumerate_solutions(data.as_bytes(), limit)
+}
+
+#[wasm_bindgen(js_name = checkUniqueness)]
+pub fn check_uniqueness(data: &str) -> String {
+    json::check_uniqueness(data.as_bytes())
+}
+
+#[wasm_bindgen(js_name = countSolutions)]
+pub fn count_solutions(data: &str, cap: usize) -> String {
+    json::count_solutions(data.as_bytes(), cap)
+}
+
+#[wasm_bindgen(js_name = nextHint)]
+pub fn next_hint(data: &str, grid: &str) -> String {
+    json::next_hint(data.as_bytes(), grid.as_bytes())
+}
+
+#[wasm_bindgen(js_name = checkGrid)]
+pub fn check_grid(data: &str, grid: &str) -> String {
+    json::check_grid(data.as_bytes(), grid.as_bytes())
+}
+
+#[wasm_bindgen(js_name = minimizeClues)]
+pub fn minimize_clues(data: &str) -> String {
+    json::minimize_clues(data.as_bytes())
+}
+
+#[wasm_bindgen(js_name = redundantRules)]
+pub fn redundant_rules(data: &str) -> String {
+    json::redundant_rules(data.as_bytes())
+}
+
+#[wasm_bindgen(js_name = rateDifficulty)]
+pub fn rate_difficulty(data: &str) -> String {
+    json::rate_difficulty(data.as_bytes())
+}
+
+#[wasm_bindgen(js_name = explainContradiction)]
+pub fn explain_contradiction(data: &str) -> String {
+    json::explain_contradiction(data.as_bytes())
+}
+
+#[wasm_bindgen(js_name = verifyGrid)]
+pub fn verify_grid(data: &str, grid: &str) -> String {
+    json::verify_grid(data.as_bytes(), grid.as_bytes())
+}
+
+#[wasm_bindgen(js_name = generatePuzzle)]
+pub fn generate_puzzle(config: &str) -> String {
+    json::generate_puzzle(config.as_bytes())
+}
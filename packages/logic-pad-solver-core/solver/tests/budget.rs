@@ -0,0 +1,23 @@
+use solver::text::parse_puzzle;
+use solver::{solve_with_budget, solve_with_progress, Budget, BudgetedSolution, Color};
+
+#[test]
+fn progress_reports_facts_before_timeout() {
+    // The first call finds a solution and the second proves the fixed tile, after which the budget runs out.
+    let puzzle = parse_puzzle("3x1\nO.#\n").unwrap();
+    let budget = Budget { max_solver_calls: Some(2), ..Budget::default() };
+    let mut facts = vec![];
+    let result = solve_with_progress(&puzzle, &budget, |y, x, color| facts.push((y, x, color))).unwrap();
+    assert!(matches!(result, BudgetedSolution::Timeout { .. }));
+    assert_eq!(facts, vec![(0, 0, Color::White)]);
+}
+
+#[test]
+fn timeout_keeps_proven_facts() {
+    let puzzle = parse_puzzle("3x1\nO.#\n").unwrap();
+    let budget = Budget { max_solver_calls: Some(2), ..Budget::default() };
+    match solve_with_budget(&puzzle, &budget).unwrap() {
+        BudgetedSolution::Timeout { facts } => assert_eq!(facts, vec![vec![Some(Color::White), None, None]]),
+        result => panic!("{:?}", result),
+    }
+}
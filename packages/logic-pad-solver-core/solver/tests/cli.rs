@@ -0,0 +1,75 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_logic-pad-solve"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn print_puzzle_from_text() {
+    let output = run(&["--print-puzzle"], "O.\n--\ncellCount dark 1\n");
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        concat!(
+            r#"{"version":1,"width":2,"height":1,"tiles":[[{"exists":true,"fixed":true,"color":"light"},"#,
+            r#"{"exists":true,"fixed":false,"color":"gray"}]],"rules":[{"type":"cellCount","color":"dark","count":1}],"#,
+            r#""connections":[]}"#,
+            "\n"
+        )
+    );
+}
+
+#[test]
+fn print_puzzle_from_json_migrates_version() {
+    let json = r#"{"width":1,"height":1,"tiles":[[{"exists":true,"fixed":false,"color":"gray"}]],"rules":[],"connections":[]}"#;
+    let output = run(&["--print-puzzle", "-"], json);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with(r#"{"version":1,"width":1,"#));
+}
+
+#[test]
+fn solve_text() {
+    let output = run(&[], "O.\n--\ncellCount dark 1\n");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "O#\n");
+}
+
+#[test]
+fn solve_json_output() {
+    let output = run(&["--json"], "O.\n--\ncellCount dark 1\n");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "[[\"light\",\"dark\"]]\n");
+}
+
+#[test]
+fn no_solution_fails() {
+    let output = run(&[], "O\n--\ncellCount dark 1\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "no solution\n");
+}
+
+#[test]
+fn invalid_puzzle_is_reported() {
+    let output = run(&[], ".\n--\ncellCount gray 1\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "-: rule with gray color\n");
+}
+
+#[test]
+fn unknown_option_is_rejected() {
+    let output = run(&["--bogus"], "");
+    assert_eq!(output.status.code(), Some(2));
+}
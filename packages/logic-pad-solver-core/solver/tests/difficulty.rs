@@ -0,0 +1,20 @@
+use solver::difficulty::rate_difficulty;
+use solver::generate::SymbolKind;
+use solver::{generate, Color, GeneratorConfig, Rule};
+
+#[test]
+fn rates_a_full_size_puzzle() {
+    let config = GeneratorConfig {
+        width: 10,
+        height: 10,
+        rules: vec![Rule::ConnectAll { color: Color::Black }],
+        symbols: vec![SymbolKind::Viewpoint, SymbolKind::Minesweeper, SymbolKind::Letter],
+        seed: 1,
+    };
+    let puzzle = generate(&config).unwrap().unwrap().puzzle;
+    let undecided = puzzle.tiles.iter().flatten().filter(|tile| !tile.fixed).count();
+
+    let difficulty = rate_difficulty(&puzzle).unwrap().unwrap();
+    assert_eq!(difficulty.rounds.iter().map(|round| round.cells).sum::<usize>(), undecided);
+    assert!(difficulty.rounds.iter().all(|round| round.cells > 0));
+}
@@ -0,0 +1,109 @@
+use solver::explain::{
+    enumerate_clues, explain_contradiction, minimize_clues, next_hint, redundant_rules, restrict, with_kept_symbols,
+    Clue,
+};
+use solver::text::parse_puzzle;
+use solver::{solve, Color, Rule};
+
+#[test]
+fn restrict_drops_symbols_without_symbol_count() {
+    let puzzle = parse_puzzle("...\n...\n--\nviewpoint 0 0 2\nviewpoint 1 2 3\nsymbolCount exactly 1 dark\n").unwrap();
+    let clues = enumerate_clues(&puzzle);
+    assert_eq!(
+        clues,
+        vec![
+            Clue::Symbol { rule: 0, index: 0, y: 0, x: 0 },
+            Clue::Symbol { rule: 0, index: 1, y: 1, x: 2 },
+            Clue::Rule { rule: 1 },
+        ]
+    );
+
+    let expected = parse_puzzle("...\n...\n--\nviewpoint 1 2 3\n").unwrap();
+    assert_eq!(restrict(&puzzle, &clues[1..2]).rules, expected.rules);
+}
+
+#[test]
+fn restrict_keeps_symbols_counted_by_kept_symbol_count() {
+    let puzzle = parse_puzzle("...\n...\n--\nviewpoint 0 0 2\nviewpoint 1 2 3\nsymbolCount exactly 1 dark\n").unwrap();
+    assert_eq!(restrict(&puzzle, &[Clue::Rule { rule: 1 }]).rules, puzzle.rules);
+}
+
+#[test]
+fn restrict_keeps_symbols_with_lying_symbols() {
+    let puzzle = parse_puzzle("...\n...\n--\nlyingSymbols 1\nviewpoint 0 0 2\nviewpoint 1 2 3\n").unwrap();
+    let clues = enumerate_clues(&puzzle);
+    assert_eq!(clues, vec![Clue::Rule { rule: 0 }]);
+    assert_eq!(restrict(&puzzle, &clues).rules, puzzle.rules);
+    assert_eq!(restrict(&puzzle, &[]).rules, vec![Rule::Viewpoint { tiles: vec![] }]);
+}
+
+#[test]
+fn contradiction_under_symbol_count_keeps_its_symbols() {
+    // Dropping the minesweeper number alone would leave the light region without symbols, which contradicts
+    // symbolCount by itself. The explanation must not rely on that, and lists the number kept by symbolCount.
+    let puzzle =
+        parse_puzzle("O.\n--\nminesweeper 0 0 1\nsymbolCount exactly 1 light\ncellCount dark 0\n").unwrap();
+    let clues = explain_contradiction(&puzzle).unwrap().unwrap();
+    assert_eq!(
+        clues,
+        vec![Clue::Symbol { rule: 0, index: 0, y: 0, x: 0 }, Clue::Rule { rule: 1 }, Clue::Rule { rule: 2 }]
+    );
+    assert_eq!(solver::solve(&restrict(&puzzle, &clues), false).unwrap(), None);
+}
+
+#[test]
+fn hint_under_symbol_count_lists_its_symbols() {
+    // No light region may contain a symbol, so the lettered cell is dark.
+    let puzzle = parse_puzzle("2x1\n.O\n--\nletter 0 0 A\nsymbolCount exactly 0 light\n").unwrap();
+    let hint = next_hint(&puzzle, &[vec![Color::Undecided; 2]]).unwrap().unwrap();
+    assert_eq!((hint.y, hint.x, hint.color), (0, 0, Color::Black));
+    assert_eq!(hint.clues, vec![Clue::Symbol { rule: 0, index: 0, y: 0, x: 0 }, Clue::Rule { rule: 1 }]);
+}
+
+#[test]
+fn minimize_keeps_symbols_counted_by_symbol_count() {
+    let puzzle = parse_puzzle("2x1\n..\n--\nsymbolCount exactly 1 light\nletter 0 0 A\ncellCount dark 1\n").unwrap();
+    let minimized = minimize_clues(&puzzle).unwrap().unwrap();
+    assert_eq!(minimized.removed, vec![]);
+    assert_eq!(minimized.puzzle, puzzle);
+}
+
+// The letter could be dropped without changing the solution, but symbols are never dropped under lyingSymbols.
+#[test]
+fn minimize_keeps_symbols_under_lying_symbols() {
+    let puzzle =
+        parse_puzzle("2x1\n..\n--\nlyingSymbols 1\nviewpoint 0 0 5\nletter 0 1 A\ncellCount dark 0\n").unwrap();
+    let minimized = minimize_clues(&puzzle).unwrap().unwrap();
+    assert_eq!(minimized.removed, vec![]);
+    assert_eq!(minimized.puzzle, puzzle);
+}
+
+// Without offByX, the viewpoint forces the other cell to be light instead of dark.
+#[test]
+fn rule_changing_the_solution_is_not_redundant() {
+    let puzzle = parse_puzzle("2x1\nO.\n--\noffByX 1\nviewpoint 0 0 2\n").unwrap();
+    let without_off_by_x = parse_puzzle("2x1\nO.\n--\nviewpoint 0 0 2\n").unwrap();
+    assert_eq!(solve(&puzzle, false).unwrap(), Some(vec![vec![Some(Color::White), Some(Color::Black)]]));
+    assert_eq!(solve(&without_off_by_x, false).unwrap(), Some(vec![vec![Some(Color::White), Some(Color::White)]]));
+    assert_eq!(redundant_rules(&puzzle).unwrap(), Some(vec![]));
+}
+
+#[test]
+fn kept_symbols_are_listed() {
+    let puzzle = parse_puzzle("...\n...\n--\nviewpoint 0 0 2\nviewpoint 1 2 3\nsymbolCount exactly 1 dark\n").unwrap();
+    let all_symbols = vec![
+        Clue::Symbol { rule: 0, index: 0, y: 0, x: 0 },
+        Clue::Symbol { rule: 0, index: 1, y: 1, x: 2 },
+        Clue::Rule { rule: 1 },
+    ];
+    assert_eq!(with_kept_symbols(&puzzle, &[Clue::Rule { rule: 1 }]), all_symbols);
+    let symbol = [Clue::Symbol { rule: 0, index: 1, y: 1, x: 2 }];
+    assert_eq!(with_kept_symbols(&puzzle, &symbol), symbol);
+}
+
+// Without the viewpoint, the lie has no symbol to fall on; without lyingSymbols, the viewpoint cannot hold.
+#[test]
+fn lying_symbols_and_their_symbols_are_not_redundant() {
+    let puzzle = parse_puzzle("1x1\nO\n--\nlyingSymbols 1\nviewpoint 0 0 5\n").unwrap();
+    assert_eq!(redundant_rules(&puzzle).unwrap(), Some(vec![]));
+}
@@ -0,0 +1,55 @@
+use std::collections::HashSet;
+
+use solver::generate::SymbolKind;
+use solver::puzzle::ViewpointTile;
+use solver::{check_uniqueness, generate, verify, Color, GeneratorConfig, Rule, Uniqueness};
+
+fn config(seed: u64) -> GeneratorConfig {
+    GeneratorConfig {
+        width: 4,
+        height: 4,
+        rules: vec![
+            Rule::ConnectAll { color: Color::Black },
+            Rule::Viewpoint { tiles: vec![ViewpointTile { y: 0, x: 0, number: 2 }] },
+        ],
+        symbols: vec![SymbolKind::Viewpoint, SymbolKind::Letter, SymbolKind::Minesweeper, SymbolKind::Galaxy],
+        seed,
+    }
+}
+
+#[test]
+fn same_seed_gives_same_puzzle() {
+    for seed in 0..4 {
+        let generated = generate(&config(seed)).unwrap().unwrap();
+        let regenerated = generate(&config(seed)).unwrap().unwrap();
+        assert_eq!(generated.puzzle, regenerated.puzzle);
+        assert_eq!(generated.solution, regenerated.solution);
+
+        assert_eq!(verify(&generated.puzzle, &generated.solution).unwrap(), vec![]);
+        match check_uniqueness(&generated.puzzle).unwrap() {
+            Uniqueness::Unique { solution } => assert_eq!(solution, generated.solution),
+            _ => panic!("generated puzzle is not unique"),
+        }
+    }
+}
+
+// Including the viewpoint given in the rules
+#[test]
+fn one_symbol_per_cell() {
+    for seed in 0..4 {
+        let generated = generate(&config(seed)).unwrap().unwrap();
+        let mut positions = HashSet::new();
+        for rule in &generated.puzzle.rules {
+            let doubled = match rule {
+                Rule::Minesweeper { tiles } => tiles.iter().map(|t| (t.y * 2, t.x * 2)).collect::<Vec<_>>(),
+                Rule::Letter { tiles } => tiles.iter().map(|t| (t.y * 2, t.x * 2)).collect(),
+                Rule::Viewpoint { tiles } => tiles.iter().map(|t| (t.y * 2, t.x * 2)).collect(),
+                Rule::Galaxy { tiles } => tiles.iter().map(|t| (t.y, t.x)).collect(),
+                _ => vec![],
+            };
+            for position in doubled {
+                assert!(positions.insert(position), "two symbols at {:?}", position);
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use solver::puzzle::CURRENT_VERSION;
+use solver::text::parse_puzzle;
+use solver::{Puzzle, SolverError};
+
+// Every kind of rule, symbol, tile and connection
+const ALL_RULES: &str = "3x3
+O#.
+.. 
+...
+--
+connectAll dark
+forbiddenPattern O./.#
+sameShape light
+uniqueShape dark
+regionArea light 2
+cellCount dark 3
+offByX 1
+symbolCount atLeast 1 light
+lyingSymbols 1
+minesweeper 0 0 1
+number 1 1 2
+letter 2 0 A
+dart 2 1 up-right 1
+viewpoint 2 2 3
+lotus 2 2 up
+galaxy 3 3
+connect 2 0 2 1
+";
+
+#[test]
+fn round_trip() {
+    let puzzle = parse_puzzle(ALL_RULES).unwrap();
+    assert_eq!(Puzzle::from_json(&puzzle.to_json()).unwrap(), puzzle);
+}
+
+#[test]
+fn version_0_is_migrated() {
+    let json = r#"{"width":2,"height":1,"tiles":[[{"exists":true,"fixed":true,"color":"light"},
+        {"exists":true,"fixed":false,"color":"gray"}]],"rules":[{"type":"cellCount","color":"dark","count":1}],
+        "connections":[]}"#;
+    let puzzle = Puzzle::from_json(json).unwrap();
+    assert_eq!(puzzle.version, CURRENT_VERSION);
+    assert_eq!(puzzle, parse_puzzle("2x1\nO.\n--\ncellCount dark 1\n").unwrap());
+    assert_eq!(Puzzle::from_json(&puzzle.to_json()).unwrap(), puzzle);
+}
+
+#[test]
+fn newer_version_is_rejected() {
+    let version = CURRENT_VERSION + 1;
+    let json = format!(r#"{{"version":{},"width":1,"height":1,"tiles":[[]],"rules":[],"connections":[]}}"#, version);
+    assert_eq!(Puzzle::from_json(&json), Err(SolverError::UnsupportedVersion { version }));
+}
+
+#[test]
+fn invalid_version_is_rejected() {
+    let json = r#"{"version":-1,"width":1,"height":1,"tiles":[[]],"rules":[],"connections":[]}"#;
+    assert!(matches!(Puzzle::from_json(json), Err(SolverError::InvalidJson { .. })));
+}
@@ -0,0 +1,42 @@
+use solver::text::parse_puzzle;
+use solver::{enumerate_solutions, verify, Board, Color, Puzzle};
+
+// All grids which satisfy `puzzle` according to `verify`, sorted.
+fn brute_force(puzzle: &Puzzle) -> Vec<Board> {
+    let cells = (0..puzzle.height)
+        .flat_map(|y| (0..puzzle.width).map(move |x| (y, x)))
+        .filter(|&(y, x)| puzzle.tiles[y][x].exists)
+        .collect::<Vec<_>>();
+    let mut ret = vec![];
+    for bits in 0..(1u32 << cells.len()) {
+        let mut grid = vec![vec![None; puzzle.width]; puzzle.height];
+        for (i, &(y, x)) in cells.iter().enumerate() {
+            grid[y][x] = Some(if bits >> i & 1 == 1 { Color::Black } else { Color::White });
+        }
+        if verify(puzzle, &grid).unwrap().is_empty() {
+            ret.push(grid);
+        }
+    }
+    ret.sort();
+    ret
+}
+
+fn check(text: &str) {
+    let puzzle = parse_puzzle(text).unwrap();
+    let expected = brute_force(&puzzle);
+    assert!(!expected.is_empty());
+
+    let mut solutions = enumerate_solutions(&puzzle, expected.len() + 1).unwrap();
+    solutions.sort();
+    assert_eq!(solutions, expected);
+}
+
+#[test]
+fn lying_letters() {
+    check("3x2\n...\n...\n--\nlyingSymbols 2\nletter 0 0 A\nletter 0 2 A\nletter 1 1 B\nconnectAll dark\n");
+}
+
+#[test]
+fn lying_area_numbers() {
+    check("3x2\n...\n...\n--\nlyingSymbols 1\nnumber 0 0 2\nnumber 1 2 3\ncellCount dark 2\n");
+}
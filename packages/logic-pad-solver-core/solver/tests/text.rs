@@ -0,0 +1,68 @@
+use solver::text::{format_board, format_puzzle, parse_puzzle};
+use solver::{solve, Rule, SolverError};
+
+fn round_trip(text: &str) {
+    let puzzle = parse_puzzle(text).unwrap();
+    assert_eq!(format_puzzle(&puzzle), text);
+    assert_eq!(parse_puzzle(&format_puzzle(&puzzle)).unwrap(), puzzle);
+}
+
+#[test]
+fn round_trip_trailing_empty_row() {
+    let puzzle = parse_puzzle("3x2\n.O.\n\n").unwrap();
+    assert_eq!(puzzle.height, 2);
+    assert!(puzzle.tiles[1].iter().all(|tile| !tile.exists));
+    round_trip("3x2\n.O.\n   \n");
+}
+
+#[test]
+fn round_trip_letters() {
+    round_trip("3x2\n...\n...\n--\nletter 0 0 A\nletter 1 2 B\nletter 0 2 A\n");
+}
+
+#[test]
+fn round_trip_merged_rules() {
+    let puzzle = parse_puzzle("2x2\n..\n..\n--\nletter 0 0 A\nconnectAll dark\nletter 1 1 A\n").unwrap();
+    assert_eq!(puzzle.rules.len(), 2);
+    assert!(matches!(&puzzle.rules[0], Rule::Letter { tiles } if tiles.len() == 2));
+    round_trip(&format_puzzle(&puzzle));
+}
+
+#[test]
+fn round_trip_rule_order() {
+    let text = "2x2\n..\n..\n--\nviewpoint 0 0 2\ncellCount dark 1\nconnectAll light\nminesweeper 1 1 1\n";
+    round_trip(text);
+    let puzzle = parse_puzzle(text).unwrap();
+    assert!(matches!(puzzle.rules[0], Rule::Viewpoint { .. }));
+    assert!(matches!(puzzle.rules[3], Rule::Minesweeper { .. }));
+}
+
+#[test]
+fn header_checks_grid_size() {
+    assert_eq!(parse_puzzle("2x3\n..\n..\n--\n").unwrap_err().line, 4);
+    assert_eq!(parse_puzzle("2x2\n..\n...\n").unwrap_err().line, 3);
+    let puzzle = parse_puzzle("3x1\n.\n").unwrap();
+    assert_eq!(puzzle.width, 3);
+    assert!(!puzzle.tiles[0][2].exists);
+}
+
+#[test]
+fn format_board_rejects_size_mismatch() {
+    let puzzle = parse_puzzle("2x1\n..\n").unwrap();
+    assert_eq!(format_board(&puzzle, &vec![vec![None]]), Err(SolverError::GridSizeMismatch));
+    assert_eq!(format_board(&puzzle, &vec![vec![None, None]]).unwrap(), "..\n");
+}
+
+#[test]
+fn fixtures() {
+    for name in ["minesweeper", "number", "letter", "dart", "viewpoint", "lotus", "symbol_count"] {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/");
+        let text = std::fs::read_to_string(format!("{}{}.txt", dir, name)).unwrap();
+        let expected = std::fs::read_to_string(format!("{}{}.expected", dir, name)).unwrap();
+
+        let puzzle = parse_puzzle(&text).unwrap();
+        assert_eq!(format_puzzle(&puzzle), text, "{}", name);
+        let board = solve(&puzzle, true).unwrap().unwrap();
+        assert_eq!(format_board(&puzzle, &board).unwrap(), expected, "{}", name);
+    }
+}
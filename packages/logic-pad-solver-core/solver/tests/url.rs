@@ -0,0 +1,82 @@
+use std::io::{Read, Write};
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use flate2::read::ZlibDecoder;
+use flate2::write::{DeflateEncoder, GzEncoder};
+use flate2::Compression;
+use solver::text::parse_puzzle;
+use solver::url::{parse_url, to_url};
+
+fn fixture(name: &str) -> String {
+    std::fs::read_to_string(format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+}
+
+// The decompressed data of a share link, as `<serializer version>_<puzzle>`.
+fn decompressed(url: &str) -> String {
+    let data = url.trim().split_once("?d=").unwrap().1.replace("%2B", "+").replace("%2F", "/").replace("%3D", "=");
+    let payload = data.strip_prefix("dfl_").unwrap();
+    let mut ret = String::new();
+    ZlibDecoder::new(&BASE64.decode(payload).unwrap()[..]).read_to_string(&mut ret).unwrap();
+    ret
+}
+
+fn gzip(data: &str) -> String {
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(data.as_bytes()).unwrap();
+    BASE64.encode(encoder.finish().unwrap())
+}
+
+fn raw_deflate(data: &str) -> String {
+    let mut encoder = DeflateEncoder::new(vec![], Compression::default());
+    encoder.write_all(data.as_bytes()).unwrap();
+    BASE64.encode(encoder.finish().unwrap())
+}
+
+#[test]
+fn share_link_fixture() {
+    let puzzle = parse_url(&fixture("share_link.url")).unwrap();
+    assert_eq!(puzzle, parse_puzzle(&fixture("share_link.txt")).unwrap());
+}
+
+#[test]
+fn round_trip() {
+    let puzzle = parse_puzzle(&fixture("share_link.txt")).unwrap();
+    assert_eq!(parse_url(&to_url(&puzzle).unwrap()).unwrap(), puzzle);
+}
+
+#[test]
+fn round_trip_puts_rules_before_symbols() {
+    let puzzle = parse_puzzle("1x1\n.\n--\nviewpoint 0 0 1\ncellCount dark 1\nletter 0 0 A\noffByX 1\n").unwrap();
+    let expected = parse_puzzle("1x1\n.\n--\ncellCount dark 1\noffByX 1\nviewpoint 0 0 1\nletter 0 0 A\n").unwrap();
+    assert_eq!(parse_url(&to_url(&puzzle).unwrap()).unwrap(), expected);
+}
+
+#[test]
+fn round_trip_non_ascii_letters() {
+    // `é` is escaped as `%E9` and `𝒜` as a surrogate pair of `%uXXXX`.
+    let puzzle = parse_puzzle("2x1\n..\n--\nletter 0 0 é\nletter 0 1 𝒜\n").unwrap();
+    assert_eq!(parse_url(&to_url(&puzzle).unwrap()).unwrap(), puzzle);
+}
+
+#[test]
+fn to_url_writes_versioned_deflate() {
+    let puzzle = parse_puzzle(&fixture("share_link.txt")).unwrap();
+    let data = decompressed(&to_url(&puzzle).unwrap());
+    assert!(data.starts_with("0_{"), "{}", data);
+}
+
+#[test]
+fn other_compressors_are_accepted() {
+    let puzzle = parse_puzzle(&fixture("share_link.txt")).unwrap();
+    let data = decompressed(&fixture("share_link.url"));
+
+    assert_eq!(parse_url(&format!("gzip_{}", gzip(&data))).unwrap(), puzzle);
+    assert_eq!(parse_url(&format!("dfl_{}", raw_deflate(&data))).unwrap(), puzzle);
+}
+
+#[test]
+fn unsupported_version_is_rejected() {
+    let data = decompressed(&fixture("share_link.url")).replacen("0_", "1_", 1);
+    assert_eq!(parse_url(&format!("gzip_{}", gzip(&data))).unwrap_err().message, "unsupported serializer version: 1");
+}
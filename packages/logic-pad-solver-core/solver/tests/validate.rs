@@ -0,0 +1,30 @@
+use solver::text::parse_puzzle;
+use solver::{validate, SolverError};
+
+#[test]
+fn numbers_out_of_range_are_only_unsatisfiable() {
+    // Under offByX, a number may differ from every possible count.
+    let puzzle = parse_puzzle("...\n...\n--\noffByX 1\nminesweeper 0 0 9\nnumber 1 1 0\nviewpoint 0 2 0\n").unwrap();
+    assert_eq!(validate(&puzzle), vec![]);
+}
+
+#[test]
+fn problems_are_reported_by_rule() {
+    let puzzle = parse_puzzle("..\n--\ncellCount dark -1\noffByX 1\noffByX 2\nminesweeper 3 0 1\n").unwrap();
+    assert_eq!(
+        validate(&puzzle),
+        vec![
+            SolverError::InvalidNumber { rule: 0 },
+            SolverError::MultipleOffByX { rule: 2 },
+            SolverError::SymbolOutOfBounds { rule: 3, y: 3, x: 0 },
+        ]
+    );
+}
+
+#[test]
+fn lying_symbols_beyond_symbol_count_are_only_unsatisfiable() {
+    // Dropping symbols (e.g. in minimizeClues) must not turn the puzzle into an invalid one.
+    let puzzle = parse_puzzle("..\n--\nlyingSymbols 2\nviewpoint 0 0 1\n").unwrap();
+    assert_eq!(validate(&puzzle), vec![]);
+    assert_eq!(solver::solve(&puzzle, false).unwrap(), None);
+}
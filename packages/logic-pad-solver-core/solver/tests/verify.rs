@@ -0,0 +1,112 @@
+use solver::text::{parse_board, parse_puzzle};
+use solver::{verify, Violation};
+
+// Checks that `good` satisfies the puzzle and that `bad` violates exactly `violations`.
+fn check(puzzle: &str, good: &str, bad: &str, violations: &[Violation]) {
+    let puzzle = parse_puzzle(puzzle).unwrap();
+    assert_eq!(verify(&puzzle, &parse_board(good).unwrap()).unwrap(), vec![], "{}", good);
+    assert_eq!(verify(&puzzle, &parse_board(bad).unwrap()).unwrap(), violations, "{}", bad);
+}
+
+#[test]
+fn tiles() {
+    check("2x1\nO.\n", "O#", "#.", &[Violation::Tile { y: 0, x: 0 }, Violation::Tile { y: 0, x: 1 }]);
+}
+
+#[test]
+fn connections() {
+    check("2x1\n..\n--\nconnect 0 0 0 1\n", "OO", "O#", &[Violation::Connection { connection: 0 }]);
+}
+
+#[test]
+fn connect_all() {
+    check("3x1\n...\n--\nconnectAll dark\n", "##O", "#O#", &[Violation::Rule { rule: 0 }]);
+}
+
+#[test]
+fn forbidden_pattern() {
+    check("3x1\n...\n--\nforbiddenPattern ##\n", "#O#", "##O", &[Violation::Rule { rule: 0 }]);
+}
+
+#[test]
+fn minesweeper() {
+    let violation = Violation::Symbol { rule: 0, index: 0, y: 0, x: 1 };
+    check("3x1\n...\n--\nminesweeper 0 1 2\n", "#O#", "#OO", &[violation]);
+}
+
+#[test]
+fn area_number() {
+    let violation = Violation::Symbol { rule: 0, index: 0, y: 0, x: 0 };
+    check("3x1\n...\n--\nnumber 0 0 2\n", "OO#", "OOO", &[violation]);
+}
+
+#[test]
+fn letter() {
+    let violations = [
+        Violation::Symbol { rule: 0, index: 0, y: 0, x: 0 },
+        Violation::Symbol { rule: 0, index: 1, y: 0, x: 2 },
+    ];
+    check("3x1\n...\n--\nletter 0 0 A\nletter 0 2 A\n", "OOO", "O#O", &violations);
+}
+
+#[test]
+fn dart() {
+    let violation = Violation::Symbol { rule: 0, index: 0, y: 0, x: 0 };
+    check("3x1\n...\n--\ndart 0 0 right 1\n", "O#O", "O##", &[violation]);
+}
+
+#[test]
+fn viewpoint() {
+    let violation = Violation::Symbol { rule: 0, index: 0, y: 0, x: 0 };
+    check("3x1\n...\n--\nviewpoint 0 0 2\n", "OO#", "OOO", &[violation]);
+}
+
+#[test]
+fn lotus() {
+    let violation = Violation::Symbol { rule: 0, index: 0, y: 0, x: 2 };
+    check("3x1\n...\n--\nlotus 0 2 up\n", "OOO", "OO#", &[violation]);
+}
+
+#[test]
+fn galaxy() {
+    let violation = Violation::Symbol { rule: 0, index: 0, y: 0, x: 2 };
+    check("3x1\n...\n--\ngalaxy 0 2\n", "#O#", "OO#", &[violation]);
+}
+
+#[test]
+fn same_shape() {
+    check("4x1\n....\n--\nsameShape dark\n", "#O#O", "#O##", &[Violation::Rule { rule: 0 }]);
+}
+
+#[test]
+fn unique_shape() {
+    check("4x1\n....\n--\nuniqueShape dark\n", "#O##", "#O#O", &[Violation::Rule { rule: 0 }]);
+}
+
+#[test]
+fn region_area() {
+    check("3x1\n...\n--\nregionArea dark 1\n", "#O#", "##O", &[Violation::Rule { rule: 0 }]);
+}
+
+#[test]
+fn cell_count() {
+    check("3x1\n...\n--\ncellCount dark 1\n", "#OO", "##O", &[Violation::Rule { rule: 0 }]);
+}
+
+#[test]
+fn off_by_x() {
+    let violation = Violation::Symbol { rule: 1, index: 0, y: 0, x: 0 };
+    check("3x1\n...\n--\noffByX 1\nviewpoint 0 0 2\n", "OOO", "OO#", &[violation]);
+}
+
+#[test]
+fn symbol_count() {
+    let puzzle = "3x1\n...\n--\nviewpoint 0 0 1\nsymbolCount exactly 1 light\n";
+    check(puzzle, "O##", "O#O", &[Violation::Rule { rule: 1 }]);
+}
+
+#[test]
+fn lying_symbols() {
+    let puzzle = "3x1\n...\n--\nlyingSymbols 1\nviewpoint 0 0 1\nviewpoint 0 2 1\n";
+    check(puzzle, "OO#", "O#O", &[Violation::Rule { rule: 0 }]);
+}
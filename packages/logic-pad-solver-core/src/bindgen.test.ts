@@ -0,0 +1,29 @@
+import { describe, expect, test } from '@jest/globals';
+import { existsSync, readFileSync } from "fs";
+import type { PuzzleData } from "./solverBridge.js";
+
+// Only runs after `npm run build-rust-bindgen`.
+const built = existsSync("src/bindgen/solver_bg.wasm");
+
+(built ? describe : describe.skip)("bindgen", () => {
+  const puzzle: PuzzleData = {
+    width: 2,
+    height: 1,
+    connections: [],
+    tiles: [[{ exists: true, fixed: true, color: "light" }, { exists: true, fixed: false, color: "gray" }]],
+    rules: [{ type: "cellCount", color: "dark", count: 1 }],
+  };
+
+  test("smoke", async () => {
+    const bridge = await import("./bindgenBridge.js");
+    await bridge.initLogicPadSolver(readFileSync("src/bindgen/solver_bg.wasm"));
+
+    expect(bridge.solveLogicPad(puzzle, false)).toEqual([["light", "dark"]]);
+    expect(bridge.checkLogicPadUniqueness(puzzle)).toEqual({ status: "unique", solution: [["light", "dark"]] });
+    expect(bridge.verifyLogicPadGrid(puzzle, [["light", "light"]])).toEqual([{ type: "rule", rule: 0 }]);
+
+    const facts: unknown[] = [];
+    expect(bridge.solveLogicPadWithProgress(puzzle, {}, (fact) => facts.push(fact))).toEqual({ status: "solved", facts: [["light", "dark"]] });
+    expect(facts).toEqual([{ x: 0, y: 0, color: "light" }, { x: 1, y: 0, color: "dark" }]);
+  });
+});
@@ -0,0 +1,57 @@
+import type {
+  Board,
+  Budget,
+  BudgetedSolverResult,
+  Color,
+  ContradictionResult,
+  CountResult,
+  DifficultyResult,
+  EnumerateResult,
+  Fact,
+  GenerateResult,
+  GeneratorConfig,
+  GridCheckResult,
+  HintResult,
+  MinimizeResult,
+  PuzzleData,
+  RedundantRulesResult,
+  SolverResult,
+  UniquenessResult,
+  VerifyResult,
+} from "./solverBridge.js";
+
+/*
+The same functions as `solverBridge.js`, backed by the wasm-bindgen build in `src/bindgen` (`npm run build-rust-bindgen`).
+`initLogicPadSolver` must be awaited before any other function is called. `moduleOrPath` is passed to the
+wasm-bindgen initializer (e.g. the bytes of `solver_bg.wasm` on Node.js); if omitted, the module is fetched
+next to `solver.js`.
+*/
+export declare function initLogicPadSolver(moduleOrPath?: BufferSource | WebAssembly.Module | URL | string): Promise<void>;
+
+export declare function solveLogicPad(data: PuzzleData, underclued: boolean): SolverResult;
+
+export declare function solveLogicPadWithBudget(data: PuzzleData, budget: Budget): BudgetedSolverResult;
+
+export declare function solveLogicPadWithProgress(data: PuzzleData, budget: Budget, onFact: (fact: Fact) => void): BudgetedSolverResult;
+
+export declare function enumerateLogicPadSolutions(data: PuzzleData, limit: number): EnumerateResult;
+
+export declare function checkLogicPadUniqueness(data: PuzzleData): UniquenessResult;
+
+export declare function countLogicPadSolutions(data: PuzzleData, cap: number): CountResult;
+
+export declare function getLogicPadHint(data: PuzzleData, grid: Color[][]): HintResult;
+
+export declare function checkLogicPadGrid(data: PuzzleData, grid: Color[][]): GridCheckResult;
+
+export declare function minimizeLogicPadClues(data: PuzzleData): MinimizeResult;
+
+export declare function findRedundantLogicPadRules(data: PuzzleData): RedundantRulesResult;
+
+export declare function rateLogicPadDifficulty(data: PuzzleData): DifficultyResult;
+
+export declare function explainLogicPadContradiction(data: PuzzleData): ContradictionResult;
+
+export declare function verifyLogicPadGrid(data: PuzzleData, grid: Board | Color[][]): VerifyResult;
+
+export declare function generateLogicPadPuzzle(config: GeneratorConfig): GenerateResult;
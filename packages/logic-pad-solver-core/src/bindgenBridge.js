@@ -0,0 +1,65 @@
+import init, * as Solver from "./bindgen/solver.js";
+
+export async function initLogicPadSolver(moduleOrPath) {
+  await init(moduleOrPath === undefined ? undefined : { module_or_path: moduleOrPath });
+}
+
+function call(f, data, ...args) {
+  return JSON.parse(f(JSON.stringify(data), ...args));
+}
+
+export function solveLogicPad(data, underclued) {
+  return call(Solver.solvePuzzle, data, underclued);
+}
+
+export function solveLogicPadWithBudget(data, budget) {
+  return call(Solver.solvePuzzleWithBudget, data, budget.timeLimitMs, budget.maxSolverCalls);
+}
+
+export function solveLogicPadWithProgress(data, budget, onFact) {
+  return call(Solver.solvePuzzleWithProgress, data, budget.timeLimitMs, budget.maxSolverCalls, (fact) => onFact(JSON.parse(fact)));
+}
+
+export function enumerateLogicPadSolutions(data, limit) {
+  return call(Solver.enumerateSolutions, data, limit);
+}
+
+export function checkLogicPadUniqueness(data) {
+  return call(Solver.checkUniqueness, data);
+}
+
+export function countLogicPadSolutions(data, cap) {
+  return call(Solver.countSolutions, data, cap);
+}
+
+export function getLogicPadHint(data, grid) {
+  return call(Solver.nextHint, data, JSON.stringify(grid));
+}
+
+export function checkLogicPadGrid(data, grid) {
+  return call(Solver.checkGrid, data, JSON.stringify(grid));
+}
+
+export function minimizeLogicPadClues(data) {
+  return call(Solver.minimizeClues, data);
+}
+
+export function findRedundantLogicPadRules(data) {
+  return call(Solver.redundantRules, data);
+}
+
+export function rateLogicPadDifficulty(data) {
+  return call(Solver.rateDifficulty, data);
+}
+
+export function explainLogicPadContradiction(data) {
+  return call(Solver.explainContradiction, data);
+}
+
+export function verifyLogicPadGrid(data, grid) {
+  return call(Solver.verifyGrid, data, JSON.stringify(grid));
+}
+
+export function generateLogicPadPuzzle(config) {
+  return call(Solver.generatePuzzle, config);
+}
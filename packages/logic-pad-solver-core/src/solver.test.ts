@@ -1,5 +1,5 @@
 import { describe, expect, test } from '@jest/globals';
-import { PuzzleData, solveLogicPad, Tile } from "./solverBridge.js";
+import { checkLogicPadGrid, checkLogicPadUniqueness, countLogicPadSolutions, enumerateLogicPadSolutions, explainLogicPadContradiction, Fact, findRedundantLogicPadRules, generateLogicPadPuzzle, getLogicPadHint, minimizeLogicPadClues, PuzzleData, rateLogicPadDifficulty, solveLogicPad, solveLogicPadWithBudget, solveLogicPadWithProgress, Tile, verifyLogicPadGrid } from "./solverBridge.js";
 
 function makeTilesData(data: string[]): Tile[][] {
   const ret: Tile[][] = []
@@ -412,6 +412,19 @@ describe("lotus", () => {
     };
     expect(solveLogicPad(puzzle, true)).toEqual({
       "error": "lotus on invalid position",
+      "code": "invalidLotusPosition",
+      "rule": 0,
+      "x": 4,
+      "y": 3,
+      "problems": [
+        {
+          "error": "lotus on invalid position",
+          "code": "invalidLotusPosition",
+          "rule": 0,
+          "x": 4,
+          "y": 3,
+        },
+      ],
     })
   });
 
@@ -437,6 +450,19 @@ describe("lotus", () => {
     };
     expect(solveLogicPad(puzzle, true)).toEqual({
       "error": "lotus on invalid position",
+      "code": "invalidLotusPosition",
+      "rule": 0,
+      "x": 3,
+      "y": 3,
+      "problems": [
+        {
+          "error": "lotus on invalid position",
+          "code": "invalidLotusPosition",
+          "rule": 0,
+          "x": 3,
+          "y": 3,
+        },
+      ],
     })
   });
 });
@@ -520,6 +546,19 @@ describe("galaxy", () => {
     };
     expect(solveLogicPad(puzzle, true)).toEqual({
       "error": "galaxies on corners may cause unexpected behavior",
+      "code": "galaxyOnCorner",
+      "rule": 0,
+      "x": 3,
+      "y": 3,
+      "problems": [
+        {
+          "error": "galaxies on corners may cause unexpected behavior",
+          "code": "galaxyOnCorner",
+          "rule": 0,
+          "x": 3,
+          "y": 3,
+        },
+      ],
     });
   });
 });
@@ -702,3 +741,596 @@ describe("symbolCountWithGalaxy", () => {
     ]));
   });
 });
+
+describe("lyingSymbols", () => {
+  const puzzle = (number: number): PuzzleData => ({
+    width: 2,
+    height: 1,
+    connections: [],
+    tiles: makeTilesData([
+      "O.",
+    ]),
+    rules: [
+      { type: "minesweeper", tiles: [{ x: 0, y: 0, number: 1 }] },
+      { type: "lyingSymbols", number },
+    ],
+  });
+
+  test("no lies", () => {
+    expect(solveLogicPad(puzzle(0), false)).toEqual(makeExpectedData(["O#"]));
+  });
+
+  test("lie", () => {
+    expect(solveLogicPad(puzzle(1), false)).toEqual(makeExpectedData(["OO"]));
+  });
+
+  test("too many lies", () => {
+    expect(solveLogicPad(puzzle(2), false)).toBeNull();
+  });
+
+  test("verify", () => {
+    expect(verifyLogicPadGrid(puzzle(1), makeExpectedData(["OO"]))).toEqual([]);
+    expect(verifyLogicPadGrid(puzzle(1), makeExpectedData(["O#"]))).toEqual([{ type: "rule", rule: 1 }]);
+  });
+});
+
+describe("enumerate", () => {
+  const puzzle: PuzzleData = {
+    width: 3,
+    height: 1,
+    connections: [],
+    tiles: makeTilesData([
+      "O..",
+    ]),
+    rules: [
+      { type: "cellCount", color: "dark", count: 1 },
+    ],
+  };
+
+  test("all", () => {
+    const solutions = enumerateLogicPadSolutions(puzzle, 10);
+    expect(solutions).toHaveLength(2);
+    expect(solutions).toEqual(expect.arrayContaining([
+      makeExpectedData(["O#O"]),
+      makeExpectedData(["OO#"]),
+    ]));
+  });
+
+  test("limit", () => {
+    expect(enumerateLogicPadSolutions(puzzle, 1)).toHaveLength(1);
+  });
+});
+
+describe("uniqueness", () => {
+  test("unique", () => {
+    const puzzle: PuzzleData = {
+      width: 3,
+      height: 1,
+      connections: [],
+      tiles: makeTilesData([
+        "O.O",
+      ]),
+      rules: [
+        { type: "cellCount", color: "dark", count: 1 },
+      ],
+    };
+    expect(checkLogicPadUniqueness(puzzle)).toEqual({
+      status: "unique",
+      solution: makeExpectedData(["O#O"]),
+    });
+  });
+
+  test("multiple", () => {
+    const puzzle: PuzzleData = {
+      width: 3,
+      height: 1,
+      connections: [],
+      tiles: makeTilesData([
+        "O..",
+      ]),
+      rules: [
+        { type: "cellCount", color: "dark", count: 1 },
+      ],
+    };
+    const result = checkLogicPadUniqueness(puzzle);
+    expect(result).toHaveProperty("status", "multiple");
+    if ("solutions" in result) {
+      expect(result.solutions[0]).not.toEqual(result.solutions[1]);
+    }
+  });
+
+  test("none", () => {
+    const puzzle: PuzzleData = {
+      width: 3,
+      height: 1,
+      connections: [],
+      tiles: makeTilesData([
+        "O#O",
+      ]),
+      rules: [
+        { type: "cellCount", color: "dark", count: 2 },
+      ],
+    };
+    expect(checkLogicPadUniqueness(puzzle)).toEqual({ status: "none" });
+  });
+});
+
+describe("count", () => {
+  const puzzle: PuzzleData = {
+    width: 4,
+    height: 1,
+    connections: [],
+    tiles: makeTilesData([
+      "O...",
+    ]),
+    rules: [
+      { type: "cellCount", color: "dark", count: 1 },
+    ],
+  };
+
+  test("exact", () => {
+    expect(countLogicPadSolutions(puzzle, 3)).toEqual({ count: 3, exact: true });
+    expect(countLogicPadSolutions(puzzle, 10)).toEqual({ count: 3, exact: true });
+  });
+
+  test("capped", () => {
+    expect(countLogicPadSolutions(puzzle, 2)).toEqual({ count: 2, exact: false });
+  });
+});
+
+describe("budget", () => {
+  const puzzle: PuzzleData = {
+    width: 5,
+    height: 3,
+    connections: [],
+    tiles: makeTilesData([
+      " ....",
+      "..#..",
+      "O.#.O",
+    ]),
+    rules: [
+      { type: "connectAll", color: "light" },
+    ],
+  };
+
+  test("solved", () => {
+    expect(solveLogicPadWithBudget(puzzle, { maxSolverCalls: 100 })).toEqual({ status: "solved", facts: solveLogicPad(puzzle, true) });
+    expect(solveLogicPadWithBudget(puzzle, {})).toEqual({ status: "solved", facts: solveLogicPad(puzzle, true) });
+  });
+
+  test("timeout", () => {
+    expect(solveLogicPadWithBudget(puzzle, { maxSolverCalls: 1 })).toEqual({ status: "timeout", facts: makeExpectedData([
+      "     ",
+      "     ",
+      "     ",
+    ]) });
+    // The light path between the two fixed tiles has to go through the top row.
+    expect(solveLogicPadWithBudget(puzzle, { maxSolverCalls: 4 })).toEqual({ status: "timeout", facts: makeExpectedData([
+      " OOO ",
+      "     ",
+      "     ",
+    ]) });
+  });
+
+  test("progress", () => {
+    const facts: Fact[] = [];
+    const result = solveLogicPadWithProgress(puzzle, {}, (fact) => facts.push(fact));
+    expect(result).toEqual({ status: "solved", facts: solveLogicPad(puzzle, true) });
+
+    const board = makeExpectedData(["     ", "     ", "     "]);
+    for (const fact of facts) {
+      board[fact.y][fact.x] = fact.color;
+    }
+    expect(board).toEqual(solveLogicPad(puzzle, true));
+  });
+
+  test("progress before timeout", () => {
+    // The first call finds a solution and the second proves the fixed tile, after which the budget runs out.
+    const small: PuzzleData = { width: 3, height: 1, connections: [], tiles: makeTilesData(["O.#"]), rules: [] };
+    const facts: Fact[] = [];
+    const result = solveLogicPadWithProgress(small, { maxSolverCalls: 2 }, (fact) => facts.push(fact));
+    expect(result).toMatchObject({ status: "timeout" });
+    expect(facts).toEqual([{ x: 0, y: 0, color: "light" }]);
+  });
+
+  test("none", () => {
+    expect(solveLogicPadWithBudget({ ...puzzle, tiles: makeTilesData(["O####", "#####", "####O"]) }, { maxSolverCalls: 100 })).toEqual({ status: "none" });
+  });
+});
+
+describe("hint", () => {
+  test("rule", () => {
+    const puzzle: PuzzleData = {
+      width: 3,
+      height: 1,
+      connections: [],
+      tiles: makeTilesData([
+        "O.O",
+      ]),
+      rules: [
+        { type: "cellCount", color: "dark", count: 1 },
+        {
+          type: "forbiddenPattern",
+          pattern: [
+            [{ exists: true, fixed: true, color: "dark" }, { exists: true, fixed: true, color: "dark" }],
+          ]
+        },
+      ],
+    };
+    expect(getLogicPadHint(puzzle, [["gray", "gray", "gray"]])).toEqual({
+      x: 1,
+      y: 0,
+      color: "dark",
+      clues: [{ type: "rule", rule: 0 }],
+    });
+  });
+
+  test("grid", () => {
+    const puzzle: PuzzleData = {
+      width: 4,
+      height: 1,
+      connections: [],
+      tiles: makeTilesData([
+        "O...",
+      ]),
+      rules: [
+        { type: "cellCount", color: "dark", count: 1 },
+      ],
+    };
+    expect(getLogicPadHint(puzzle, [["gray", "gray", "gray", "gray"]])).toBeNull();
+    expect(getLogicPadHint(puzzle, [["light", "light", "light", "gray"]])).toEqual({
+      x: 3,
+      y: 0,
+      color: "dark",
+      clues: [{ type: "rule", rule: 0 }],
+    });
+  });
+});
+
+describe("check grid", () => {
+  test("mistake", () => {
+    const puzzle: PuzzleData = {
+      width: 3,
+      height: 1,
+      connections: [],
+      tiles: makeTilesData([
+        "O.O",
+      ]),
+      rules: [
+        { type: "cellCount", color: "dark", count: 1 },
+      ],
+    };
+    expect(checkLogicPadGrid(puzzle, [["gray", "gray", "gray"]])).toEqual({ mistakes: [], completable: true });
+    expect(checkLogicPadGrid(puzzle, [["gray", "light", "gray"]])).toEqual({ mistakes: [{ x: 1, y: 0 }], completable: false });
+  });
+
+  test("not completable", () => {
+    const puzzle: PuzzleData = {
+      width: 4,
+      height: 1,
+      connections: [],
+      tiles: makeTilesData([
+        "O...",
+      ]),
+      rules: [
+        { type: "cellCount", color: "dark", count: 1 },
+      ],
+    };
+    expect(checkLogicPadGrid(puzzle, [["gray", "light", "light", "light"]])).toEqual({ mistakes: [], completable: false });
+  });
+});
+
+describe("minimize", () => {
+  test("fixed tile", () => {
+    const puzzle: PuzzleData = {
+      width: 4,
+      height: 1,
+      connections: [],
+      tiles: makeTilesData([
+        "O#..",
+      ]),
+      rules: [
+        { type: "cellCount", color: "dark", count: 1 },
+      ],
+    };
+    expect(minimizeLogicPadClues(puzzle)).toEqual({
+      puzzle: { ...puzzle, version: 1, tiles: makeTilesData([".#.."]) },
+      removed: [{ type: "fixedTile", x: 0, y: 0 }],
+    });
+  });
+
+  test("symbol", () => {
+    const puzzle: PuzzleData = {
+      width: 3,
+      height: 1,
+      connections: [],
+      tiles: makeTilesData([
+        "...",
+      ]),
+      rules: [
+        { type: "cellCount", color: "dark", count: 1 },
+        { type: "viewpoint", tiles: [{ x: 0, y: 0, number: 2 }, { x: 2, y: 0, number: 1 }] },
+      ],
+    };
+    expect(minimizeLogicPadClues(puzzle)).toEqual({
+      puzzle: { ...puzzle, version: 1, rules: [puzzle.rules[0], { type: "viewpoint", tiles: [{ x: 0, y: 0, number: 2 }] }] },
+      removed: [{ type: "symbol", rule: 1, index: 1, x: 2, y: 0 }],
+    });
+  });
+
+  test("not unique", () => {
+    const puzzle: PuzzleData = {
+      width: 2,
+      height: 1,
+      connections: [],
+      tiles: makeTilesData([
+        "..",
+      ]),
+      rules: [],
+    };
+    expect(minimizeLogicPadClues(puzzle)).toBeNull();
+  });
+});
+
+describe("redundant rules", () => {
+  test("redundant", () => {
+    const puzzle: PuzzleData = {
+      width: 3,
+      height: 1,
+      connections: [],
+      tiles: makeTilesData([
+        "#..",
+      ]),
+      rules: [
+        { type: "cellCount", color: "dark", count: 1 },
+        { type: "connectAll", color: "light" },
+        { type: "regionArea", color: "light", size: 2 },
+      ],
+    };
+    expect(findRedundantLogicPadRules(puzzle)).toEqual([1, 2]);
+  });
+
+  test("not unique", () => {
+    const puzzle: PuzzleData = {
+      width: 2,
+      height: 1,
+      connections: [],
+      tiles: makeTilesData([
+        "..",
+      ]),
+      rules: [
+        { type: "connectAll", color: "light" },
+      ],
+    };
+    expect(findRedundantLogicPadRules(puzzle)).toBeNull();
+  });
+});
+
+describe("difficulty", () => {
+  test("propagation", () => {
+    const puzzle: PuzzleData = {
+      width: 4,
+      height: 1,
+      connections: [],
+      tiles: makeTilesData([
+        ".#..",
+      ]),
+      rules: [
+        { type: "cellCount", color: "dark", count: 1 },
+      ],
+    };
+    expect(rateLogicPadDifficulty(puzzle)).toEqual({
+      rounds: [{ strength: "propagation", cells: 3 }],
+      maxStrength: "propagation",
+      score: 1,
+    });
+  });
+
+  test("solved", () => {
+    const puzzle: PuzzleData = {
+      width: 2,
+      height: 1,
+      connections: [],
+      tiles: makeTilesData([
+        "O#",
+      ]),
+      rules: [],
+    };
+    expect(rateLogicPadDifficulty(puzzle)).toEqual({ rounds: [], maxStrength: null, score: 0 });
+  });
+
+  test("not unique", () => {
+    const puzzle: PuzzleData = {
+      width: 2,
+      height: 1,
+      connections: [],
+      tiles: makeTilesData([
+        "..",
+      ]),
+      rules: [],
+    };
+    expect(rateLogicPadDifficulty(puzzle)).toBeNull();
+  });
+});
+
+describe("contradiction", () => {
+  test("rule", () => {
+    const puzzle: PuzzleData = {
+      width: 3,
+      height: 1,
+      connections: [],
+      tiles: makeTilesData([
+        "O#O",
+      ]),
+      rules: [
+        { type: "connectAll", color: "dark" },
+        { type: "cellCount", color: "dark", count: 2 },
+      ],
+    };
+    expect(explainLogicPadContradiction(puzzle)).toEqual([{ type: "rule", rule: 1 }]);
+  });
+
+  test("symbol", () => {
+    const puzzle: PuzzleData = {
+      width: 3,
+      height: 1,
+      connections: [],
+      tiles: makeTilesData([
+        "O..",
+      ]),
+      rules: [
+        { type: "minesweeper", tiles: [{ x: 0, y: 0, number: 2 }] },
+        { type: "cellCount", color: "dark", count: 1 },
+      ],
+    };
+    expect(explainLogicPadContradiction(puzzle)).toEqual([{ type: "symbol", rule: 0, index: 0, x: 0, y: 0 }]);
+  });
+
+  test("solvable", () => {
+    const puzzle: PuzzleData = {
+      width: 3,
+      height: 1,
+      connections: [],
+      tiles: makeTilesData([
+        "O..",
+      ]),
+      rules: [
+        { type: "cellCount", color: "dark", count: 1 },
+      ],
+    };
+    expect(explainLogicPadContradiction(puzzle)).toBeNull();
+  });
+});
+
+describe("verify", () => {
+  const puzzle: PuzzleData = {
+    width: 3,
+    height: 2,
+    connections: [],
+    tiles: makeTilesData([
+      "O..",
+      "...",
+    ]),
+    rules: [
+      { type: "connectAll", color: "dark" },
+      { type: "number", tiles: [{ x: 0, y: 0, number: 2 }] },
+    ],
+  };
+
+  test("solution", () => {
+    expect(verifyLogicPadGrid(puzzle, makeExpectedData([
+      "O##",
+      "O##",
+    ]))).toEqual([]);
+  });
+
+  test("violations", () => {
+    expect(verifyLogicPadGrid(puzzle, makeExpectedData([
+      "#O#",
+      "#O.",
+    ]))).toEqual([
+      { type: "tile", x: 0, y: 0 },
+      { type: "tile", x: 2, y: 1 },
+      { type: "rule", rule: 0 },
+    ]);
+  });
+});
+
+describe("generate", () => {
+  test("unique", () => {
+    const result = generateLogicPadPuzzle({
+      width: 4,
+      height: 4,
+      rules: [{ type: "connectAll", color: "dark" }],
+      symbols: ["number", "minesweeper"],
+      seed: 42,
+    });
+    if (result === null || "code" in result) {
+      throw new Error("generation failed");
+    }
+    expect(result.puzzle.rules[0]).toEqual({ type: "connectAll", color: "dark" });
+    expect(checkLogicPadUniqueness(result.puzzle)).toEqual({ status: "unique", solution: result.solution });
+  });
+
+  test("deterministic", () => {
+    const config = { width: 3, height: 3, symbols: ["viewpoint" as const], seed: 7 };
+    expect(generateLogicPadPuzzle(config)).toEqual(generateLogicPadPuzzle(config));
+  });
+
+  test("no solution", () => {
+    expect(generateLogicPadPuzzle({
+      width: 2,
+      height: 1,
+      rules: [{ type: "cellCount", color: "dark", count: 3 }],
+    })).toBeNull();
+  });
+});
+
+test("invalid json error", () => {
+  const puzzle = {
+    width: 3,
+    height: 1,
+    connections: [],
+    tiles: makeTilesData([
+      "O..",
+    ]),
+    rules: [
+      { type: "cellCount", color: "dark", count: "one" },
+    ],
+  };
+  const result = solveLogicPad(puzzle as unknown as PuzzleData, false);
+  expect(result).toHaveProperty("code", "invalidJson");
+  expect(result).toHaveProperty("error");
+});
+
+describe("version", () => {
+  const puzzle: PuzzleData = {
+    width: 2,
+    height: 1,
+    connections: [],
+    tiles: makeTilesData([
+      "#.",
+    ]),
+    rules: [
+      { type: "cellCount", color: "dark", count: 1 },
+    ],
+  };
+
+  test("supported", () => {
+    expect(solveLogicPad({ ...puzzle, version: 0 }, false)).toEqual(makeExpectedData(["#O"]));
+    expect(solveLogicPad({ ...puzzle, version: 1 }, false)).toEqual(makeExpectedData(["#O"]));
+  });
+
+  test("unsupported", () => {
+    expect(solveLogicPad({ ...puzzle, version: 99 }, false)).toEqual({
+      error: "unsupported puzzle version 99",
+      code: "unsupportedVersion",
+      version: 99,
+    });
+  });
+});
+
+test("validation reports all problems", () => {
+  const puzzle: PuzzleData = {
+    width: 3,
+    height: 1,
+    connections: [{ x1: 0, y1: 0, x2: 3, y2: 0 }],
+    tiles: makeTilesData([
+      "O..",
+    ]),
+    rules: [
+      { type: "sameShape", color: "gray" },
+      { type: "minesweeper", tiles: [{ x: 5, y: 0, number: 1 }] },
+    ],
+  };
+  expect(solveLogicPad(puzzle, false)).toEqual({
+    "error": "connection out of bounds",
+    "code": "connectionOutOfBounds",
+    "connection": 0,
+    "problems": [
+      { "error": "connection out of bounds", "code": "connectionOutOfBounds", "connection": 0 },
+      { "error": "rule with gray color", "code": "grayColor", "rule": 0 },
+      { "error": "symbol out of bounds", "code": "symbolOutOfBounds", "rule": 1, "x": 5, "y": 0 },
+    ],
+  });
+});
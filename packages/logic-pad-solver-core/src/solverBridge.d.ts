@@ -22,6 +22,7 @@ export type Rule =
   | { type: "cellCount"; color: Color; count: number }
   | { type: "offByX"; number: number }
   | { type: "symbolCount"; number: number; kind: "exactly" | "atMost" | "atLeast"; color: Color }
+  | { type: "lyingSymbols"; number: number }
   | { type: "minesweeper"; tiles: readonly { x: number; y: number; number: number }[] }
   | { type: "number"; tiles: readonly { x: number; y: number; number: number }[] }
   | { type: "letter"; tiles: readonly { x: number; y: number; letter: string }[] }
@@ -30,7 +31,12 @@ export type Rule =
   | { type: "lotus"; tiles: readonly { x: number; y: number; orientation: Orientation }[] }
   | { type: "galaxy"; tiles: readonly { x: number; y: number; }[] }
 
+/*
+`version` is the version of the format (1 is the current one; omitted in version 0), and older versions are migrated
+by the solver. `schema/puzzle.schema.json` is the JSON Schema of the current version, generated from the Rust types.
+*/
 export interface PuzzleData {
+  version?: number;
   width: number;
   height: number;
   connections: { x1: number; y1: number; x2: number; y2: number }[];
@@ -38,6 +44,201 @@ export interface PuzzleData {
   rules: Rule[];
 }
 
-export type SolverResult = { error: string } | null | ("dark" | "light" | null)[][];
+export type Board = ("dark" | "light" | null)[][];
+
+/*
+`error` is a human-readable message. `rule` is an index of `PuzzleData.rules`, `connection` is an index of
+`PuzzleData.connections` and `x` / `y` are the coordinates of the offending tile (doubled for "lotus" and "galaxy");
+they are present only when applicable.
+If the puzzle fails validation, the first problem is reported at the top level and `problems` lists all of them.
+*/
+export type ErrorCode =
+    "invalidJson"
+  | "unsupportedVersion"
+  | "invalidSize"
+  | "tileRowCountMismatch"
+  | "tileColumnCountMismatch"
+  | "connectionOutOfBounds"
+  | "fixedGrayTile"
+  | "grayColor"
+  | "invalidForbiddenPattern"
+  | "emptyForbiddenPattern"
+  | "invalidNumber"
+  | "duplicateAreaNumber"
+  | "symbolOnNonExistingTile"
+  | "symbolOutOfBounds"
+  | "invalidLotusPosition"
+  | "galaxyOnCorner"
+  | "symbolNotOnCellCenter"
+  | "multipleOffByX"
+  | "negativeOffByX"
+  | "multipleAreaNumberRules"
+  | "multipleLyingSymbolsRules"
+  | "multipleRegionAreaRules"
+  | "gridSizeMismatch"
+  | "inconsistentGrid"
+  | "unsupportedTimeLimit"
+  | "inconsistentSolver";
+
+export interface SolverError {
+  error: string;
+  code: ErrorCode;
+  message?: string;
+  version?: number;
+  rule?: number;
+  connection?: number;
+  x?: number;
+  y?: number;
+  problems?: SolverError[];
+}
+
+export type SolverResult = SolverError | null | Board;
+
+/*
+Limits on the work done by `solveLogicPadWithBudget`; omitted fields mean no limit.
+The budget is checked between solver calls, so a single call may overrun `timeLimitMs`.
+*/
+export interface Budget {
+  timeLimitMs?: number;
+  maxSolverCalls?: number;
+}
+
+// `facts` is the same as the result of `solveLogicPad` with `underclued`; on "timeout", only the cells proven so far are filled.
+export type BudgetedSolverResult =
+    SolverError
+  | { status: "none" }
+  | { status: "solved"; facts: Board }
+  | { status: "timeout"; facts: Board };
+
+// A cell which is proven to have `color` in all solutions.
+export interface Fact {
+  x: number;
+  y: number;
+  color: "dark" | "light";
+}
+
+export type EnumerateResult = SolverError | Board[];
+
+export type UniquenessResult =
+    SolverError
+  | { status: "none" }
+  | { status: "unique"; solution: Board }
+  | { status: "multiple"; solutions: [Board, Board] };
+
+/*
+A clue which is used in a hint or an explanation of a contradiction. `rule` is an index of `PuzzleData.rules`, and `index` is an index of `tiles` of the rule.
+A symbolCount or lyingSymbols rule in a clue set brings all symbols with it, and they are listed in a hint or an explanation of a contradiction.
+"fixedTile" and "connection" (an index of `PuzzleData.connections`) appear only in the result of `minimizeLogicPadClues`.
+*/
+export type Clue =
+    { type: "rule"; rule: number }
+  | { type: "symbol"; rule: number; index: number; x: number; y: number }
+  | { type: "fixedTile"; x: number; y: number }
+  | { type: "connection"; connection: number };
+
+export type HintResult = SolverError | null | { x: number; y: number; color: "dark" | "light"; clues: Clue[] };
+
+/*
+`mistakes` are colored cells whose color is excluded by every solution. `completable` is whether some solution agrees
+with all colored cells.
+*/
+export type GridCheckResult = SolverError | { mistakes: { x: number; y: number }[]; completable: boolean };
+
+/*
+`null` if the puzzle does not have a unique solution. `removed` lists the clues of the original puzzle which were dropped
+while keeping the same unique solution. Symbols are never dropped from a puzzle with a symbolCount or lyingSymbols
+rule.
+*/
+export type MinimizeResult = SolverError | null | { puzzle: PuzzleData; removed: Clue[] };
+
+/*
+Indices of `PuzzleData.rules` without each of which the solution would still be unique and the same.
+Each rule is checked on its own, so dropping two of them at once may make the solution ambiguous.
+`null` if the puzzle does not have a unique solution.
+*/
+export type RedundantRulesResult = SolverError | null | number[];
+
+/*
+The kind of deduction which finds a cell: "propagation" if a single symbol or global rule forces it, "probing" if
+one step of propagation from its other color runs into a contradiction, and "deepProbing" if its other color
+contradicts the whole puzzle. Tiles found so far, connections and offByX always take part, and symbolCount only in
+"deepProbing".
+*/
+export type Strength = "propagation" | "probing" | "deepProbing";
+
+/*
+The puzzle is solved in rounds, each of which fills every cell deducible with the weakest sufficient strength.
+`score` is the sum of the weights of the rounds (1 for "propagation", 4 for "probing" and 16 for "deepProbing").
+`null` if the puzzle does not have a unique solution.
+*/
+export type DifficultyResult =
+    SolverError
+  | null
+  | { rounds: { strength: Strength; cells: number }[]; maxStrength: Strength | null; score: number };
+
+// `null` if the puzzle has a solution
+export type ContradictionResult = SolverError | null | Clue[];
+
+// `exact` is false if there are more than `count` (= `cap`) solutions.
+export type CountResult = SolverError | { count: number; exact: boolean };
+
+/*
+A part of the puzzle which a grid does not satisfy. "tile" is an uncolored tile or a fixed tile colored differently.
+`rule`, `index`, `x` and `y` are as in `Clue`.
+*/
+export type Violation =
+    { type: "tile"; x: number; y: number }
+  | { type: "connection"; connection: number }
+  | { type: "rule"; rule: number }
+  | { type: "symbol"; rule: number; index: number; x: number; y: number };
+
+export type VerifyResult = SolverError | Violation[];
+
+export type SymbolKind = "minesweeper" | "number" | "letter" | "dart" | "viewpoint" | "lotus" | "galaxy";
+
+/*
+`rules` are kept as they are in the generated puzzle, and clues are symbols of the kinds in `symbols` (and fixed tiles
+only where no symbol helps). The same `seed` always yields the same puzzle.
+*/
+export interface GeneratorConfig {
+  width: number;
+  height: number;
+  rules?: Rule[];
+  symbols?: SymbolKind[];
+  seed?: number;
+}
+
+// `null` if `rules` have no solution
+export type GenerateResult = SolverError | null | { puzzle: PuzzleData; solution: Board };
 
 export declare function solveLogicPad(data: PuzzleData, underclued: boolean): SolverResult;
+
+export declare function solveLogicPadWithBudget(data: PuzzleData, budget: Budget): BudgetedSolverResult;
+
+/*
+Same as `solveLogicPadWithBudget`, but calls `onFact` for each proven cell.
+Unlike `solveLogicPadWithBudget`, the solver is called repeatedly even without any limit, which is slower.
+*/
+export declare function solveLogicPadWithProgress(data: PuzzleData, budget: Budget, onFact: (fact: Fact) => void): BudgetedSolverResult;
+
+export declare function enumerateLogicPadSolutions(data: PuzzleData, limit: number): EnumerateResult;
+
+export declare function checkLogicPadUniqueness(data: PuzzleData): UniquenessResult;
+
+export declare function countLogicPadSolutions(data: PuzzleData, cap: number): CountResult;
+
+export declare function getLogicPadHint(data: PuzzleData, grid: Color[][]): HintResult;
+
+export declare function checkLogicPadGrid(data: PuzzleData, grid: Color[][]): GridCheckResult;
+
+export declare function minimizeLogicPadClues(data: PuzzleData): MinimizeResult;
+
+export declare function findRedundantLogicPadRules(data: PuzzleData): RedundantRulesResult;
+
+export declare function rateLogicPadDifficulty(data: PuzzleData): DifficultyResult;
+
+export declare function explainLogicPadContradiction(data: PuzzleData): ContradictionResult;
+
+export declare function verifyLogicPadGrid(data: PuzzleData, grid: Board | Color[][]): VerifyResult;
+
+export declare function generateLogicPadPuzzle(config: GeneratorConfig): GenerateResult;
@@ -2,20 +2,95 @@ import Module from "./core/solver.js";
 
 let Solver = null;
 
-export function solveLogicPad(data, underclued) {
+function withJsonBuffer(value, call) {
+  const encoded = new TextEncoder().encode(JSON.stringify(value));
+  const buf = Solver._malloc(encoded.length);
+  Solver.HEAPU8.set(encoded, buf);
+
+  const res = call(buf, encoded.length);
+  Solver._free(buf);
+  return res;
+}
+
+function callSolver(data, call) {
   if (Solver === null) {
     Solver = Module();
   }
 
-  const dataEncoded = new TextEncoder().encode(JSON.stringify(data));
-  const buf = Solver._malloc(dataEncoded.length);
-  Solver.HEAPU8.set(dataEncoded, buf);
+  const res = withJsonBuffer(data, call);
 
-  let res = Solver._solve_puzzle(buf, dataEncoded.length, underclued ? 1 : 0);
-  Solver._free(buf);
+  const ptr = Solver._result_data(res);
+  const length = Solver._result_len(res);
+  const ansStr = new TextDecoder().decode(Solver.HEAPU8.slice(ptr, ptr + length));
+  Solver._free_result(res);
+  return JSON.parse(ansStr);
+}
+
+export function solveLogicPad(data, underclued) {
+  return callSolver(data, (buf, len) => Solver._solve_puzzle(buf, len, underclued ? 1 : 0));
+}
 
-  const length = Solver.HEAPU8[res] | (Solver.HEAPU8[res + 1] << 8) | (Solver.HEAPU8[res + 2] << 16) | (Solver.HEAPU8[res + 3] << 24);
+export function solveLogicPadWithBudget(data, budget) {
+  const timeLimitMs = budget.timeLimitMs ?? -1;
+  const maxSolverCalls = budget.maxSolverCalls ?? -1;
+  return callSolver(data, (buf, len) => Solver._solve_puzzle_with_budget(buf, len, timeLimitMs, maxSolverCalls));
+}
 
-  const ansStr = new TextDecoder().decode(Solver.HEAPU8.slice(res + 4, res + 4 + length));
-  return JSON.parse(ansStr);
+export function solveLogicPadWithProgress(data, budget, onFact) {
+  const timeLimitMs = budget.timeLimitMs ?? -1;
+  const maxSolverCalls = budget.maxSolverCalls ?? -1;
+  return callSolver(data, (buf, len) => {
+    const callback = Solver.addFunction((ptr, length) => {
+      onFact(JSON.parse(new TextDecoder().decode(Solver.HEAPU8.slice(ptr, ptr + length))));
+    }, "vii");
+    try {
+      return Solver._solve_puzzle_with_progress(buf, len, timeLimitMs, maxSolverCalls, callback);
+    } finally {
+      Solver.removeFunction(callback);
+    }
+  });
+}
+
+export function enumerateLogicPadSolutions(data, limit) {
+  return callSolver(data, (buf, len) => Solver._enumerate_solutions(buf, len, limit));
+}
+
+export function checkLogicPadUniqueness(data) {
+  return callSolver(data, (buf, len) => Solver._check_uniqueness(buf, len));
+}
+
+export function countLogicPadSolutions(data, cap) {
+  return callSolver(data, (buf, len) => Solver._count_solutions(buf, len, cap));
+}
+
+export function getLogicPadHint(data, grid) {
+  return callSolver(data, (buf, len) => withJsonBuffer(grid, (gridBuf, gridLen) => Solver._next_hint(buf, len, gridBuf, gridLen)));
+}
+
+export function checkLogicPadGrid(data, grid) {
+  return callSolver(data, (buf, len) => withJsonBuffer(grid, (gridBuf, gridLen) => Solver._check_grid(buf, len, gridBuf, gridLen)));
+}
+
+export function minimizeLogicPadClues(data) {
+  return callSolver(data, (buf, len) => Solver._minimize_clues(buf, len));
+}
+
+export function findRedundantLogicPadRules(data) {
+  return callSolver(data, (buf, len) => Solver._redundant_rules(buf, len));
+}
+
+export function rateLogicPadDifficulty(data) {
+  return callSolver(data, (buf, len) => Solver._rate_difficulty(buf, len));
+}
+
+export function explainLogicPadContradiction(data) {
+  return callSolver(data, (buf, len) => Solver._explain_contradiction(buf, len));
+}
+
+export function verifyLogicPadGrid(data, grid) {
+  return callSolver(data, (buf, len) => withJsonBuffer(grid, (gridBuf, gridLen) => Solver._verify_grid(buf, len, gridBuf, gridLen)));
+}
+
+export function generateLogicPadPuzzle(config) {
+  return callSolver(config, (buf, len) => Solver._generate_puzzle(buf, len));
 }